@@ -8,8 +8,11 @@ struct Demo {
     checkboxes: [bool; 3],
     background: Color,
     textbox_state: ConstStr<128>,
+    notes: String,
     log: ConstStr<32000>,
-    log_updated: bool
+    log_updated: bool,
+    follow_log: bool,
+    heading: FontId
 }
 
 fn main() {    
@@ -19,13 +22,23 @@ fn main() {
         checkboxes: Default::default(),
         background: Color::rgb(90, 95, 100),
         textbox_state: ConstStr::new(), 
+        notes: String::from("Multi-line text.\nPress Return for a new line."),
         log: ConstStr::new(),
-        log_updated: false
+        log_updated: false,
+        follow_log: true,
+        heading: FontId::default()
     }));
 }
 
 impl App for Demo {
+    fn setup(&mut self, _ctx: &mut Context, shell: &mut Shell) {
+        self.heading = shell.font_with_size(FontId::default(), 24.);
+    }
+
     fn frame(&mut self, ctx: &mut Context, shell: &mut Shell) {
+        let size = shell.screen_size();
+        ctx.set_dock_area(rect(0, 0, size.x, size.y));
+
         self.test_window(ctx, shell);
         self.log_window(ctx);
         self.style_window(ctx);
@@ -57,10 +70,18 @@ impl Demo {
                 if ctx.button("Button 1") {
                     self.write_log("Pressed button 1");
                 }
+
+                ctx.tooltip("Writes to the log");
     
                 if ctx.button("Button 2") {
                     self.write_log("Pressed button 2");
                 }
+
+                ctx.tooltip_with(|ctx| {
+                    ctx.layout_row(&[100], 0);
+                    ctx.label("Also writes");
+                    ctx.label("to the log");
+                });
     
                 ctx.label("Popup widgets:");
                 
@@ -184,6 +205,8 @@ impl Demo {
                     value_changed = true
                 }
 
+                ctx.tooltip("Drag to change the value");
+
                 if value_changed {
                     self.background = Color::rgba(r as u8, g as u8, b as u8, a as u8);
                     shell.set_clear_color(self.background);
@@ -209,6 +232,11 @@ impl Demo {
 
                 ctx.draw_widget_text(color_label, rect, WidgetColor::Text, opts);
             }
+
+            if ctx.header("Text Area", false) {
+                ctx.layout_row(&[-1], 100);
+                ctx.text_area(&mut self.notes);
+            }
         });
     }
 
@@ -224,12 +252,34 @@ impl Demo {
                 ctx.layout_row(&[-1], -1);
                 
                 ctx.text(self.log.as_str());
+
+                ContextMenu::new("Log Menu").on_body().show(ctx, |ctx| {
+                    if ctx.menu_item("Clear") {
+                        self.log.clear();
+                    }
+
+                    MenuItem::new("Save").enabled(false).draw(ctx);
+                    ctx.menu_separator();
+                    ctx.menu_check("Scroll to new lines", &mut self.follow_log);
+
+                    Submenu::new("Write").show(ctx, |ctx| {
+                        if ctx.menu_item("Hello") {
+                            self.write_log("Hello");
+                        }
+
+                        if ctx.menu_item("World") {
+                            self.write_log("World");
+                        }
+                    });
+                });
             });
 
             if self.log_updated {
-                // Scroll to bottom
-                let panel = ctx.container_mut(index);
-                panel.scroll.y = panel.content_size.y;
+                if self.follow_log {
+                    // Scroll to bottom
+                    let panel = ctx.container_mut(index);
+                    panel.scroll.y = panel.content_size.y;
+                }
 
                 self.log_updated = false;
             }
@@ -271,7 +321,11 @@ impl Demo {
             "base hover:",
             "base focus:",
             "scroll base:",
-            "scroll thumb:"
+            "scroll thumb:",
+            "selection:",
+            "focus ring:",
+            "dock preview:",
+            "text disabled:"
         ];
 
         let rect = rect(380, 250, 390, 240);
@@ -280,27 +334,40 @@ impl Demo {
             .min_size(vec2(390, 240))
             .show(ctx, |ctx|
         {
+            ctx.layout_row(&[-1], 28);
+            ctx.w(Label::new("Colors").font(self.heading));
+
             ctx.layout_row(&[55, -1], 0);
             ctx.label("Theme:");
 
             const THEMES: &[&str] = &["Default", "Catppuccin Latte", "Catppuccin Frappe", "Catppuccin Macchiato", "Catppuccin Mocha"];
 
             if ctx.w(Dropdown::new(&mut self.themes, THEMES).visible_items(5)).submit {
-                let colors = match self.themes.index.unwrap() {
-                    1 => catppuccin::LATTE.widget_colors(),
-                    2 => catppuccin::FRAPPE.widget_colors(),
-                    3 => catppuccin::MACCHIATO.widget_colors(),
-                    4 => catppuccin::MOCHA.widget_colors(),
-                    _ => WidgetColors::default()
+                let theme = match self.themes.index.unwrap() {
+                    1 => Some(catppuccin::LATTE),
+                    2 => Some(catppuccin::FRAPPE),
+                    3 => Some(catppuccin::MACCHIATO),
+                    4 => Some(catppuccin::MOCHA),
+                    _ => None
                 };
 
-                ctx.style.colors = colors;
+                match theme {
+                    Some(theme) => theme.apply(&mut ctx.style),
+                    None => {
+                        let default = Style::default();
+
+                        ctx.style.colors = default.colors;
+                        ctx.style.corner_radius = default.corner_radius;
+                        ctx.style.border_width = default.border_width;
+                        ctx.style.shadow = default.shadow;
+                    }
+                }
             }
 
             ctx.layout_row(&[-1], -1);
 
             Panel::new("Theme color editor").show(ctx, |ctx| {
-                let width = ctx.current_container().body.w as f64 * 0.14;
+                let width = ctx.logical(ctx.current_container().body.w) as f64 * 0.14;
                 let width = width as i32;
     
                 ctx.layout_row(&[96, width, width, width, width, -1], 0);
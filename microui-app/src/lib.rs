@@ -1,8 +1,8 @@
-use std::time::{Instant, Duration};
+use std::{time::{Instant, Duration}, error::Error, path::PathBuf, mem};
 
 use microui::{
-    Context, TextSizeHandler, MouseButton, ModKey,
-    CursorIcon, Color, Vec2, vec2
+    Context, TextSizeHandler, MouseButton, Key, CursorIcon, Color, FontId, ImageId, IconAtlas,
+    Vec2, InputRecording, InputPlayer, Clipboard, MemoryClipboard, UiState, vec2
 };
 use winit::{
     event::{
@@ -17,6 +17,9 @@ use winit::{
 pub use microui;
 pub use winit;
 
+/// The minimum time between two frames, capping them to 60 FPS.
+const FRAME_TIME: Duration = Duration::from_millis(16);
+
 pub trait App {
     fn setup(&mut self, _ctx: &mut Context, _shell: &mut Shell) { }
     fn frame(&mut self, ctx: &mut Context, shell: &mut Shell);
@@ -32,13 +35,64 @@ pub trait MicrouiRenderer {
     fn window(&self) -> &Window;
     fn resize(&mut self, size: PhysicalSize<u32>, scale_factor: f64);
     fn render(&mut self, ctx: &mut Context, clear_color: Option<Color>);
+    /// Creates or replaces the image drawn for `id`. `rgba` contains
+    /// `width * height` tightly packed, non-premultiplied RGBA pixels.
+    fn set_image(&mut self, id: ImageId, width: u32, height: u32, rgba: &[u8]);
+    /// Draws the icons in `atlas` from its image instead of the built-in ones.
+    fn set_icon_atlas(&mut self, atlas: Option<IconAtlas>);
+    /// Creates or replaces the font drawn for `id` from TrueType
+    /// or OpenType `data` at `size` pixels.
+    fn set_font(&mut self, id: FontId, data: &[u8], size: f32) -> Result<(), Box<dyn Error>>;
+    /// Draws `id` with the face of `font` at `size` pixels.
+    fn set_font_size(&mut self, id: FontId, font: FontId, size: f32);
+    /// Adds a font for the characters that the other fonts don't have.
+    /// Fallback fonts are tried in the order they were added.
+    fn add_fallback_font(&mut self, data: &[u8]) -> Result<(), Box<dyn Error>>;
     fn text_size_handler(&self) -> Self::TextSizeHandler;
 }
 
+/// The system clipboard, accessed through the window.
+struct WindowClipboard(window_clipboard::Clipboard);
+
 #[derive(Clone)]
 pub struct Shell {
     clear_color: Option<Color>,
-    screen_size: Vec2
+    screen_size: Vec2,
+    input_player: Option<InputPlayer>,
+    record_path: Option<PathBuf>,
+    state_path: Option<PathBuf>,
+    redraw: bool,
+    images: Vec<PendingImage>,
+    next_image: u32,
+    icon_atlas: Option<Option<IconAtlas>>,
+    fonts: Vec<PendingFont>,
+    next_font: u32
+}
+
+/// An image that is given to the renderer before the next render.
+#[derive(Clone)]
+struct PendingImage {
+    id: ImageId,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>
+}
+
+/// A font that is given to the renderer before the next frame,
+/// so that the text of that frame is measured with it.
+#[derive(Clone)]
+enum PendingFont {
+    Data {
+        id: FontId,
+        data: Vec<u8>,
+        size: f32
+    },
+    Size {
+        id: FontId,
+        font: FontId,
+        size: f32
+    },
+    Fallback(Vec<u8>)
 }
 
 pub fn run<Renderer: MicrouiRenderer + 'static>(mut app: Box<dyn App>) {
@@ -49,101 +103,147 @@ pub fn run<Renderer: MicrouiRenderer + 'static>(mut app: Box<dyn App>) {
     );
 
     let mut ctx = Context::new(renderer.text_size_handler());
+    ctx.set_scale_factor(renderer.window().scale_factor() as f32);
+
+    match window_clipboard::Clipboard::connect(renderer.window()) {
+        Ok(clipboard) => ctx.set_clipboard(WindowClipboard(clipboard)),
+        Err(e) => eprintln!("Couldn't connect to the system clipboard: {}", e)
+    }
 
     let mut mouse_pos = Vec2::ZERO;
     let mut render_delta = Instant::now();
 
+    // Frames are only drawn after input or when the last one changed something.
+    // Those we ask for are only rendered if they look different, unlike the ones
+    // the system asks for, i.e. when the window is uncovered.
+    let mut redraw = true;
+    let mut requested = false;
+    let mut force_render = true;
+
     let mut current_scale_factor = renderer.window().scale_factor();
-    let size = renderer.window().inner_size().to_logical::<i32>(current_scale_factor);
+    let size = renderer.window().inner_size();
 
-    let mut shell = Shell::new(vec2(size.width, size.height));
+    let mut shell = Shell::new(vec2(size.width as i32, size.height as i32));
 
     app.setup(&mut ctx, &mut shell);
+    load_fonts(&mut renderer, &mut shell);
+
+    if let Some(path) = shell.state_path.as_ref().filter(|x| x.exists()) {
+        match UiState::load(path) {
+            Ok(state) => ctx.load_state(&state),
+            Err(e) => eprintln!("Couldn't load UI state from {}: {}", path.display(), e)
+        }
+    }
 
     event_loop.run(move |event, _, control_flow| match event {
         Event::WindowEvent {
             ref event,
             window_id
-        } if window_id == renderer.window().id() => match event {
-            WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-            WindowEvent::Resized(physical_size) => {
-                let size = physical_size.to_logical::<i32>(current_scale_factor);
-                shell.screen_size = vec2(size.width, size.height);
+        } if window_id == renderer.window().id() => {
+            redraw = true;
 
-                renderer.resize(*physical_size, current_scale_factor);
-            }
-            WindowEvent::ScaleFactorChanged {
-                new_inner_size,
-                scale_factor
-            } => {
-                current_scale_factor = *scale_factor;
+            match event {
+                WindowEvent::CloseRequested => {
+                    if let (Some(path), Some(recording)) = (&shell.record_path, ctx.stop_recording()) {
+                        if let Err(e) = recording.save(path) {
+                            eprintln!("Couldn't save input recording to {}: {}", path.display(), e);
+                        }
+                    }
 
-                let size = new_inner_size.to_logical::<i32>(current_scale_factor);
-                shell.screen_size = vec2(size.width, size.height);
+                    if let Some(path) = &shell.state_path {
+                        if let Err(e) = ctx.save_state().save(path) {
+                            eprintln!("Couldn't save UI state to {}: {}", path.display(), e);
+                        }
+                    }
 
-                renderer.resize(**new_inner_size, current_scale_factor);
-            },
-            WindowEvent::CursorMoved { position, .. } => {
-                let position = position.to_logical::<i32>(current_scale_factor);
-                mouse_pos = vec2(position.x, position.y);
+                    *control_flow = ControlFlow::Exit
+                },
+                WindowEvent::Resized(physical_size) => {
+                    shell.screen_size = vec2(physical_size.width as i32, physical_size.height as i32);
+
+                    renderer.resize(*physical_size, current_scale_factor);
+                    force_render = true;
+                }
+                WindowEvent::ScaleFactorChanged {
+                    new_inner_size,
+                    scale_factor
+                } => {
+                    current_scale_factor = *scale_factor;
+                    ctx.set_scale_factor(current_scale_factor as f32);
+
+                    shell.screen_size = vec2(new_inner_size.width as i32, new_inner_size.height as i32);
+
+                    renderer.resize(**new_inner_size, current_scale_factor);
+                    force_render = true;
+                },
+                WindowEvent::CursorMoved { position, .. } => {
+                    mouse_pos = vec2(position.x as i32, position.y as i32);
                 
-                ctx.input_mouse_move(mouse_pos);
-            }
-            WindowEvent::MouseInput { state, button, .. } => {
-                let button = match button {
-                    WinitMouseBtn::Left => Some(MouseButton::Left),
-                    WinitMouseBtn::Right => Some(MouseButton::Right),
-                    WinitMouseBtn::Middle => Some(MouseButton::Middle),
-                    WinitMouseBtn::Other(_) => None
-                };
-
-                if let Some(button) = button {
-                    match state {
-                        ElementState::Pressed => ctx.input_mouse_down(mouse_pos, button),
-                        ElementState::Released => ctx.input_mouse_up(mouse_pos, button),
+                    ctx.input_mouse_move(mouse_pos);
+                }
+                WindowEvent::MouseInput { state, button, .. } => {
+                    let button = match button {
+                        WinitMouseBtn::Left => Some(MouseButton::Left),
+                        WinitMouseBtn::Right => Some(MouseButton::Right),
+                        WinitMouseBtn::Middle => Some(MouseButton::Middle),
+                        WinitMouseBtn::Other(_) => None
+                    };
+
+                    if let Some(button) = button {
+                        match state {
+                            ElementState::Pressed => ctx.input_mouse_down(mouse_pos, button),
+                            ElementState::Released => ctx.input_mouse_up(mouse_pos, button),
+                        }
                     }
                 }
-            }
-            WindowEvent::MouseWheel { delta, .. } => {
-                match delta {
-                    MouseScrollDelta::LineDelta(x, y) => {
-                        let speed = 30.0f32;
-                        ctx.input_scroll(vec2(-(x * speed) as i32, -(y * speed) as i32));
+                WindowEvent::MouseWheel { delta, .. } => {
+                    match delta {
+                        MouseScrollDelta::LineDelta(x, y) => {
+                            let speed = 30. * current_scale_factor as f32;
+                            ctx.input_scroll(vec2(-(x * speed) as i32, -(y * speed) as i32));
+                        }
+                        _ => unimplemented!()
                     }
-                    _ => unimplemented!()
                 }
-            }
-            WindowEvent::ReceivedCharacter(c) => {
-                // Winit also sends non-text characters here.
-                if c.is_alphanumeric() || c.is_ascii_punctuation() || *c == ' ' {
-                    let mut buf = [0; 4];
-                    let text = c.encode_utf8(&mut buf);
+                WindowEvent::ReceivedCharacter(c) => {
+                    // Winit also sends control characters such as backspace here.
+                    if !c.is_control() {
+                        let mut buf = [0; 4];
+                        let text = c.encode_utf8(&mut buf);
 
-                    ctx.input_text(&text[0..c.len_utf8()]);
-                }
-            },
-            WindowEvent::KeyboardInput { input, .. } => {
-                if let Some(key) = input.virtual_keycode {
-                    let key = match key {
-                        VirtualKeyCode::LShift | VirtualKeyCode::RShift => Some(ModKey::Shift),
-                        VirtualKeyCode::LControl | VirtualKeyCode::RControl => Some(ModKey::Ctrl),
-                        VirtualKeyCode::LAlt | VirtualKeyCode::RAlt => Some(ModKey::Alt),
-                        VirtualKeyCode::Back => Some(ModKey::Backspace),
-                        VirtualKeyCode::Return => Some(ModKey::Return),
-                        _ => None
-                    };
+                        ctx.input_text(&text[0..c.len_utf8()]);
+                    }
+                },
+                WindowEvent::KeyboardInput { input, .. } => {
+                    if let Some(key) = input.virtual_keycode {
+                        let key = map_key(key);
 
-                    if let Some(key) = key {
                         match input.state {
                             ElementState::Pressed => ctx.input_key_down(key),
                             ElementState::Released => ctx.input_key_up(key)
                         }
                     }
                 }
+                _ => {}
             }
-            _ => {}
         },
         Event::RedrawRequested(id) if id == renderer.window().id() => {
+            if shell.record_path.is_some() && !ctx.is_recording() {
+                ctx.start_recording();
+            }
+
+            if let Some(player) = &mut shell.input_player {
+                player.play(&mut ctx);
+
+                if player.is_finished() {
+                    shell.input_player = None;
+                }
+            }
+
+            if load_fonts(&mut renderer, &mut shell) {
+                force_render = true;
+            }
+
             ctx.begin();
             app.frame(&mut ctx, &mut shell);
             ctx.end();
@@ -163,36 +263,368 @@ pub fn run<Renderer: MicrouiRenderer + 'static>(mut app: Box<dyn App>) {
             };
             
             renderer.window().set_cursor_icon(cursor_icon);
-            renderer.render(&mut ctx, shell.clear_color.take());
 
+            for image in shell.images.drain(..) {
+                renderer.set_image(image.id, image.width, image.height, &image.rgba);
+                force_render = true;
+            }
+
+            if let Some(atlas) = shell.icon_atlas.take() {
+                renderer.set_icon_atlas(atlas);
+                force_render = true;
+            }
+
+            let clear_color = shell.clear_color.take();
+
+            if ctx.frame_changed() || clear_color.is_some() || force_render || !requested {
+                renderer.render(&mut ctx, clear_color);
+            }
+
+            // A change can take another frame to settle, i.e. the hover state of a widget.
+            redraw = ctx.frame_changed() || ctx.tooltip_pending() || mem::take(&mut shell.redraw) || shell.input_player.is_some();
+            requested = false;
+            force_render = false;
             render_delta = Instant::now();
         },
         Event::MainEventsCleared => {
-            // Cap to 60 FPS
-            if render_delta.elapsed() >= Duration::from_millis(16) {
+            if !redraw {
+                *control_flow = ControlFlow::Wait;
+            } else if render_delta.elapsed() >= FRAME_TIME {
                 renderer.window().request_redraw();
+                redraw = false;
+                requested = true;
+                *control_flow = ControlFlow::Wait;
+            } else {
+                *control_flow = ControlFlow::WaitUntil(render_delta + FRAME_TIME);
             }
         }
+        Event::LoopDestroyed => {
+            ctx.set_clipboard(MemoryClipboard::default());
+        }
         _ => {}
     });
 }
 
+impl Clipboard for WindowClipboard {
+    #[inline]
+    fn get(&mut self) -> Option<String> {
+        self.0.read().ok()
+    }
+
+    #[inline]
+    fn set(&mut self, text: &str) {
+        if let Err(e) = self.0.write(text.into()) {
+            eprintln!("Couldn't write to the system clipboard: {}", e);
+        }
+    }
+}
+
 impl Shell {
     #[inline]
     pub fn set_clear_color(&mut self, color: Color) {
         self.clear_color = Some(color);
     }
 
+    /// The size of the window in physical pixels, like the rects of the [`Context`].
     #[inline]
     pub fn screen_size(&self) -> Vec2 {
         self.screen_size
     }
 
+    /// Record all input from the next frame on and save it
+    /// to `path` when the window is closed.
+    #[inline]
+    pub fn record_input(&mut self, path: impl Into<PathBuf>) {
+        self.record_path = Some(path.into());
+    }
+
+    /// Restore the window positions and other UI state from `path` after
+    /// [`App::setup`] if the file exists and save it there when the window is closed.
+    #[inline]
+    pub fn persist_state(&mut self, path: impl Into<PathBuf>) {
+        self.state_path = Some(path.into());
+    }
+
+    /// Draw another frame even if there's no input, i.e. to animate something.
+    /// This needs to be called every frame for as long as the animation runs.
+    #[inline]
+    pub fn request_redraw(&mut self) {
+        self.redraw = true;
+    }
+
+    /// Uploads an image to the renderer so that it can be drawn with
+    /// [`Context::image`]. `rgba` must contain `width * height` tightly
+    /// packed, non-premultiplied RGBA pixels.
+    pub fn create_image(&mut self, width: u32, height: u32, rgba: Vec<u8>) -> ImageId {
+        assert_eq!(rgba.len(), width as usize * height as usize * 4, "Wrong image size.");
+
+        let id = ImageId(self.next_image);
+        self.next_image += 1;
+
+        self.update_image(id, width, height, rgba);
+
+        id
+    }
+
+    /// Replaces the pixels of an image created with [`Shell::create_image`].
+    pub fn update_image(&mut self, id: ImageId, width: u32, height: u32, rgba: Vec<u8>) {
+        assert_eq!(rgba.len(), width as usize * height as usize * 4, "Wrong image size.");
+
+        self.images.push(PendingImage { id, width, height, rgba });
+    }
+
+    /// Draw icons from an image created with [`Shell::create_image`].
+    /// Icons that aren't in the atlas are still drawn by the renderer.
+    #[inline]
+    pub fn set_icon_atlas(&mut self, atlas: Option<IconAtlas>) {
+        self.icon_atlas = Some(atlas);
+    }
+
+    /// Loads a TrueType or OpenType font to draw text with at `size` pixels,
+    /// i.e. with [`microui::Label::font`] or by setting [`microui::Style::font`].
+    /// Until the next frame, text with the font is drawn with the default one.
+    pub fn add_font(&mut self, data: Vec<u8>, size: f32) -> FontId {
+        let id = self.next_font_id();
+        self.fonts.push(PendingFont::Data { id, data, size });
+
+        id
+    }
+
+    /// Adds the face of `font` at another size, i.e. for headings.
+    /// [`FontId::default`] is the renderer's built-in font.
+    pub fn font_with_size(&mut self, font: FontId, size: f32) -> FontId {
+        let id = self.next_font_id();
+        self.fonts.push(PendingFont::Size { id, font, size });
+
+        id
+    }
+
+    /// Adds a font that is used for the characters the other fonts don't have,
+    /// i.e. CJK or emoji. Fallback fonts are tried in the order they were added.
+    pub fn add_fallback_font(&mut self, data: Vec<u8>) {
+        self.fonts.push(PendingFont::Fallback(data));
+        self.redraw = true;
+    }
+
+    /// Replay previously recorded input starting with the next frame.
+    /// Input from the window is still processed while replaying.
+    #[inline]
+    pub fn replay_input(&mut self, recording: InputRecording) {
+        self.input_player = Some(InputPlayer::new(recording));
+    }
+
+    #[inline]
+    fn next_font_id(&mut self) -> FontId {
+        self.next_font += 1;
+        self.redraw = true;
+
+        FontId(self.next_font)
+    }
+
     #[inline]
     fn new(screen_size: Vec2) -> Self {
         Self {
             clear_color: Some(Color::rgb(90, 95, 100)),
-            screen_size
+            screen_size,
+            input_player: None,
+            record_path: None,
+            state_path: None,
+            redraw: false,
+            images: Vec::new(),
+            next_image: 0,
+            icon_atlas: None,
+            fonts: Vec::new(),
+            next_font: 0
         }
     }
 }
+
+/// Gives the fonts added since the last frame to the renderer.
+/// Returns `true` if there were any.
+fn load_fonts(renderer: &mut impl MicrouiRenderer, shell: &mut Shell) -> bool {
+    let loaded = !shell.fonts.is_empty();
+
+    for font in shell.fonts.drain(..) {
+        match font {
+            PendingFont::Data { id, data, size } => {
+                if let Err(e) = renderer.set_font(id, &data, size) {
+                    eprintln!("Couldn't load font {}: {}", id.0, e);
+                }
+            },
+            PendingFont::Size { id, font, size } => renderer.set_font_size(id, font, size),
+            PendingFont::Fallback(data) => {
+                if let Err(e) = renderer.add_fallback_font(&data) {
+                    eprintln!("Couldn't load fallback font: {}", e);
+                }
+            }
+        }
+    }
+
+    loaded
+}
+
+fn map_key(key: VirtualKeyCode) -> Key {
+    use VirtualKeyCode as V;
+
+    match key {
+        V::Key0 => Key::Key0,
+        V::Key1 => Key::Key1,
+        V::Key2 => Key::Key2,
+        V::Key3 => Key::Key3,
+        V::Key4 => Key::Key4,
+        V::Key5 => Key::Key5,
+        V::Key6 => Key::Key6,
+        V::Key7 => Key::Key7,
+        V::Key8 => Key::Key8,
+        V::Key9 => Key::Key9,
+        V::A => Key::A,
+        V::B => Key::B,
+        V::C => Key::C,
+        V::D => Key::D,
+        V::E => Key::E,
+        V::F => Key::F,
+        V::G => Key::G,
+        V::H => Key::H,
+        V::I => Key::I,
+        V::J => Key::J,
+        V::K => Key::K,
+        V::L => Key::L,
+        V::M => Key::M,
+        V::N => Key::N,
+        V::O => Key::O,
+        V::P => Key::P,
+        V::Q => Key::Q,
+        V::R => Key::R,
+        V::S => Key::S,
+        V::T => Key::T,
+        V::U => Key::U,
+        V::V => Key::V,
+        V::W => Key::W,
+        V::X => Key::X,
+        V::Y => Key::Y,
+        V::Z => Key::Z,
+        V::F1 => Key::F1,
+        V::F2 => Key::F2,
+        V::F3 => Key::F3,
+        V::F4 => Key::F4,
+        V::F5 => Key::F5,
+        V::F6 => Key::F6,
+        V::F7 => Key::F7,
+        V::F8 => Key::F8,
+        V::F9 => Key::F9,
+        V::F10 => Key::F10,
+        V::F11 => Key::F11,
+        V::F12 => Key::F12,
+        V::F13 => Key::F13,
+        V::F14 => Key::F14,
+        V::F15 => Key::F15,
+        V::F16 => Key::F16,
+        V::F17 => Key::F17,
+        V::F18 => Key::F18,
+        V::F19 => Key::F19,
+        V::F20 => Key::F20,
+        V::F21 => Key::F21,
+        V::F22 => Key::F22,
+        V::F23 => Key::F23,
+        V::F24 => Key::F24,
+        V::Escape => Key::Escape,
+        V::Tab => Key::Tab,
+        V::Space => Key::Space,
+        V::Return => Key::Return,
+        V::Back => Key::Backspace,
+        V::Delete => Key::Delete,
+        V::Insert => Key::Insert,
+        V::Home => Key::Home,
+        V::End => Key::End,
+        V::PageUp => Key::PageUp,
+        V::PageDown => Key::PageDown,
+        V::Left => Key::Left,
+        V::Right => Key::Right,
+        V::Up => Key::Up,
+        V::Down => Key::Down,
+        V::LShift | V::RShift => Key::Shift,
+        V::LControl | V::RControl => Key::Ctrl,
+        V::LAlt | V::RAlt => Key::Alt,
+        V::LWin | V::RWin => Key::Logo,
+        V::Capital => Key::CapsLock,
+        V::Numlock => Key::NumLock,
+        V::Scroll => Key::ScrollLock,
+        V::Snapshot => Key::PrintScreen,
+        V::Pause => Key::Pause,
+        V::Apps => Key::Apps,
+        V::Compose => Key::Compose,
+        V::Numpad0 => Key::Numpad0,
+        V::Numpad1 => Key::Numpad1,
+        V::Numpad2 => Key::Numpad2,
+        V::Numpad3 => Key::Numpad3,
+        V::Numpad4 => Key::Numpad4,
+        V::Numpad5 => Key::Numpad5,
+        V::Numpad6 => Key::Numpad6,
+        V::Numpad7 => Key::Numpad7,
+        V::Numpad8 => Key::Numpad8,
+        V::Numpad9 => Key::Numpad9,
+        V::NumpadAdd => Key::NumpadAdd,
+        V::NumpadSubtract => Key::NumpadSubtract,
+        V::NumpadMultiply => Key::NumpadMultiply,
+        V::NumpadDivide => Key::NumpadDivide,
+        V::NumpadDecimal => Key::NumpadDecimal,
+        V::NumpadComma => Key::NumpadComma,
+        V::NumpadEnter => Key::NumpadEnter,
+        V::NumpadEquals => Key::NumpadEquals,
+        V::Apostrophe => Key::Apostrophe,
+        V::Asterisk => Key::Asterisk,
+        V::At => Key::At,
+        V::Backslash => Key::Backslash,
+        V::Caret => Key::Caret,
+        V::Colon => Key::Colon,
+        V::Comma => Key::Comma,
+        V::Equals => Key::Equals,
+        V::Grave => Key::Grave,
+        V::LBracket => Key::LBracket,
+        V::RBracket => Key::RBracket,
+        V::Minus => Key::Minus,
+        V::Period => Key::Period,
+        V::Plus => Key::Plus,
+        V::Semicolon => Key::Semicolon,
+        V::Slash => Key::Slash,
+        V::Underline => Key::Underline,
+        V::Copy => Key::Copy,
+        V::Cut => Key::Cut,
+        V::Paste => Key::Paste,
+        V::AbntC1 => Key::AbntC1,
+        V::AbntC2 => Key::AbntC2,
+        V::Ax => Key::Ax,
+        V::Convert => Key::Convert,
+        V::Kana => Key::Kana,
+        V::Kanji => Key::Kanji,
+        V::NoConvert => Key::NoConvert,
+        V::OEM102 => Key::Oem102,
+        V::Yen => Key::Yen,
+        V::Calculator => Key::Calculator,
+        V::Mail => Key::Mail,
+        V::MediaSelect => Key::MediaSelect,
+        V::MediaStop => Key::MediaStop,
+        V::Mute => Key::Mute,
+        V::MyComputer => Key::MyComputer,
+        V::NavigateForward => Key::NavigateForward,
+        V::NavigateBackward => Key::NavigateBackward,
+        V::NextTrack => Key::NextTrack,
+        V::PlayPause => Key::PlayPause,
+        V::PrevTrack => Key::PrevTrack,
+        V::VolumeDown => Key::VolumeDown,
+        V::VolumeUp => Key::VolumeUp,
+        V::Power => Key::Power,
+        V::Sleep => Key::Sleep,
+        V::Wake => Key::Wake,
+        V::Stop => Key::Stop,
+        V::Sysrq => Key::Sysrq,
+        V::Unlabeled => Key::Unlabeled,
+        V::WebBack => Key::WebBack,
+        V::WebFavorites => Key::WebFavorites,
+        V::WebForward => Key::WebForward,
+        V::WebHome => Key::WebHome,
+        V::WebRefresh => Key::WebRefresh,
+        V::WebSearch => Key::WebSearch,
+        V::WebStop => Key::WebStop
+    }
+}
@@ -1,10 +1,10 @@
-use std::num::NonZeroU32;
+use std::{cell::RefCell, collections::HashMap, error::Error, num::NonZeroU32, rc::Rc};
 
 use microui_app::{
     MicrouiRenderer,
     microui::{
         Context, CommandHandler, TextSizeHandler,
-        FontId, Icon, Color, Rect, Vec2
+        FontId, Icon, IconAtlas, ImageId, UvRect, Angle, Color, Rect, Vec2
     },
     winit::{
         event_loop::EventLoop,
@@ -14,9 +14,9 @@ use microui_app::{
 };
 
 use femtovg::{
-    Canvas, TextContext, Baseline, Align, Paint, Path,
-    FontId as FemtovgFontId, Color as FemtovgColor,
-    renderer::OpenGl
+    Canvas, TextContext, Baseline, Paint, Path, ImageSource, ImageFlags, Solidity, LineCap,
+    FontId as FemtovgFontId, Color as FemtovgColor, ImageId as FemtovgImageId,
+    renderer::OpenGl, imgref::Img, rgb::FromSlice
 };
 
 use glutin::{
@@ -38,14 +38,31 @@ pub struct Renderer {
     surface: Surface<WindowSurface>,
     canvas: Canvas<OpenGl>,
     text_context: TextContext,
-    font_id: FemtovgFontId,
-    clear_color: FemtovgColor
+    fonts: Fonts,
+    images: HashMap<ImageId, FemtovgImageId>,
+    icon_atlas: Option<IconAtlas>,
+    clear_color: FemtovgColor,
+    /// The core lays out in physical pixels, so the canvas has no scale of its
+    /// own and only the text is drawn larger than it's measured.
+    scale_factor: f32
 }
 
 #[derive(Clone)]
 pub struct FemtovgTextSizeHandler {
     ctx: TextContext,
-    font_id: FemtovgFontId
+    fonts: Fonts
+}
+
+/// The canvas fonts and sizes by their microui id, shared with the text size
+/// handlers so that text is measured with the font it's drawn with.
+/// Unknown ids use the default font.
+#[derive(Clone)]
+struct Fonts(Rc<RefCell<FontSet>>);
+
+struct FontSet {
+    fonts: HashMap<FontId, (FemtovgFontId, f32)>,
+    /// Tried in order by femtovg for the characters that a font doesn't have.
+    fallbacks: Vec<FemtovgFontId>
 }
 
 impl MicrouiRenderer for Renderer {
@@ -126,7 +143,8 @@ impl MicrouiRenderer for Renderer {
         let mut canvas = Canvas::new_with_text_context(renderer, text_context.clone()).unwrap();
         let font_id = canvas.add_font_mem(DEFAULT_FONT).unwrap();
 
-        canvas.set_size(width, height, window.scale_factor() as f32);
+        canvas.set_size(width, height, 1.);
+        let scale_factor = window.scale_factor() as f32;
 
         let renderer = Renderer {
             window,
@@ -134,8 +152,14 @@ impl MicrouiRenderer for Renderer {
             surface,
             canvas,
             text_context,
-            font_id,
-            clear_color: FemtovgColor::black()
+            fonts: Fonts(Rc::new(RefCell::new(FontSet {
+                fonts: HashMap::from([(FontId::default(), (font_id, FONT_SIZE_PT))]),
+                fallbacks: Vec::new()
+            }))),
+            images: HashMap::new(),
+            icon_atlas: None,
+            clear_color: FemtovgColor::black(),
+            scale_factor
         };
 
         renderer
@@ -149,7 +173,8 @@ impl MicrouiRenderer for Renderer {
             size.height.try_into().unwrap()
         );
 
-        self.canvas.set_size(size.width, size.height, scale_factor as f32);
+        self.canvas.set_size(size.width, size.height, 1.);
+        self.scale_factor = scale_factor as f32;
     }
 
     #[inline]
@@ -172,11 +197,45 @@ impl MicrouiRenderer for Renderer {
         self.surface.swap_buffers(&self.ctx).unwrap();
     }
 
+    fn set_image(&mut self, id: ImageId, width: u32, height: u32, rgba: &[u8]) {
+        let img = Img::new(rgba.as_rgba(), width as usize, height as usize);
+        let image = self.canvas.create_image(ImageSource::Rgba(img), ImageFlags::empty())
+            .expect("Couldn't create image.");
+
+        if let Some(old) = self.images.insert(id, image) {
+            self.canvas.delete_image(old);
+        }
+    }
+
+    #[inline]
+    fn set_icon_atlas(&mut self, atlas: Option<IconAtlas>) {
+        self.icon_atlas = atlas;
+    }
+
+    fn set_font(&mut self, id: FontId, data: &[u8], size: f32) -> Result<(), Box<dyn Error>> {
+        let font = self.canvas.add_font_mem(data)?;
+        self.fonts.0.borrow_mut().fonts.insert(id, (font, size));
+
+        Ok(())
+    }
+
+    fn set_font_size(&mut self, id: FontId, font: FontId, size: f32) {
+        let (font, _) = self.fonts.get(font);
+        self.fonts.0.borrow_mut().fonts.insert(id, (font, size));
+    }
+
+    fn add_fallback_font(&mut self, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        let font = self.canvas.add_font_mem(data)?;
+        self.fonts.0.borrow_mut().fallbacks.push(font);
+
+        Ok(())
+    }
+
     #[inline]
     fn text_size_handler(&self) -> Self::TextSizeHandler {
         FemtovgTextSizeHandler {
             ctx: self.text_context.clone(),
-            font_id: self.font_id
+            fonts: self.fonts.clone()
         }
     }
 }
@@ -202,14 +261,12 @@ impl CommandHandler for Renderer {
     #[inline]
     fn text_cmd(
         &mut self,
-        _font: FontId,
+        font: FontId,
         pos: Vec2,
         color: Color,
-        text: String
+        text: &str
     ) {
-        let paint = Paint::default()
-            .with_font(&[self.font_id])
-            .with_font_size(FONT_SIZE_PT)
+        let paint = self.fonts.paint(font, self.scale_factor)
             .with_text_baseline(Baseline::Top)
             .with_color(
                 FemtovgColor::rgba(color.r, color.g, color.b, color.a)
@@ -218,46 +275,159 @@ impl CommandHandler for Renderer {
         self.canvas.fill_text(pos.x as f32, pos.y as f32, text, &paint).unwrap();
     }
 
-    #[inline]
     fn icon_cmd(
         &mut self,
         id: Icon,
         rect: Rect,
         color: Color
     ) {
-        let text = match id {
-            Icon::Close => "",
-            Icon::Resize => "樂",
-            Icon::Check => "",
-            Icon::Collapsed => "",
-            Icon::Expanded => "",
-            Icon::None => return
+        if let Some(atlas) = &self.icon_atlas {
+            if let Some((dst, uv)) = atlas.locate(id, rect) {
+                self.image_cmd(atlas.image(), dst, uv, color);
+
+                return;
+            }
+        }
+
+        id.fallback(rect, |r| self.rect_cmd(r, color));
+    }
+
+    fn image_cmd(
+        &mut self,
+        id: ImageId,
+        rect: Rect,
+        uv: UvRect,
+        tint: Color
+    ) {
+        let Some(&image) = self.images.get(&id) else {
+            return;
         };
 
+        // The pattern covers the whole image, so it's scaled up
+        // and moved to only show the uv rect inside of `rect`.
+        let w = rect.w as f32 / uv.w;
+        let h = rect.h as f32 / uv.h;
+        let x = rect.x as f32 - uv.x * w;
+        let y = rect.y as f32 - uv.y * h;
+
+        let paint = Paint::image_tint(
+            image, x, y, w, h, 0.,
+            FemtovgColor::rgba(tint.r, tint.g, tint.b, tint.a)
+        );
+
+        let mut path = Path::default();
+        path.rect(rect.x as f32, rect.y as f32, rect.w as f32, rect.h as f32);
+
+        self.canvas.fill_path(&mut path, &paint);
+    }
+
+    fn line_cmd(
+        &mut self,
+        a: Vec2,
+        b: Vec2,
+        thickness: i32,
+        color: Color
+    ) {
+        let mut path = Path::default();
+        path.move_to(a.x as f32, a.y as f32);
+        path.line_to(b.x as f32, b.y as f32);
+
         let paint = Paint::default()
-            .with_font(&[self.font_id])
-            .with_font_size(FONT_SIZE_PT)
-            .with_text_baseline(Baseline::Top)
-            .with_text_align(Align::Center)
-            .with_color(
-                FemtovgColor::rgba(color.r, color.g, color.b, color.a)
-            );
-            
-        let metrics = self.canvas.measure_text(0., 0., text, &paint).unwrap();
+            .with_line_width(thickness as f32)
+            .with_line_cap(LineCap::Square)
+            .with_color(FemtovgColor::rgba(color.r, color.g, color.b, color.a));
+
+        self.canvas.stroke_path(&mut path, &paint);
+    }
+
+    fn rounded_rect_cmd(
+        &mut self,
+        rect: Rect,
+        radius: i32,
+        color: Color
+    ) {
+        let mut path = Path::default();
+        path.rounded_rect(rect.x as f32, rect.y as f32, rect.w as f32, rect.h as f32, radius as f32);
+
+        self.fill(&mut path, color);
+    }
+
+    fn circle_cmd(
+        &mut self,
+        center: Vec2,
+        radius: i32,
+        color: Color
+    ) {
+        let mut path = Path::default();
+        path.circle(center.x as f32, center.y as f32, radius as f32);
+
+        self.fill(&mut path, color);
+    }
 
-        let x = rect.x as f32 + (rect.w as f32 - metrics.width()) / 2.;
-        let y = rect.y as f32 + (rect.h as f32 - metrics.height()) / 2.;
+    fn arc_cmd(
+        &mut self,
+        center: Vec2,
+        radius: i32,
+        start: Angle,
+        end: Angle,
+        thickness: i32,
+        color: Color
+    ) {
+        // Hole winds clockwise.
+        let mut path = Path::default();
+        path.arc(center.x as f32, center.y as f32, radius as f32, start.0, end.0, Solidity::Hole);
 
-        self.canvas.fill_text(x, y, text, &paint).unwrap();
+        let paint = Paint::default()
+            .with_line_width(thickness as f32)
+            .with_color(FemtovgColor::rgba(color.r, color.g, color.b, color.a));
+
+        self.canvas.stroke_path(&mut path, &paint);
+    }
+
+    fn triangle_cmd(
+        &mut self,
+        points: [Vec2; 3],
+        color: Color
+    ) {
+        self.polygon_cmd(&points, color);
+    }
+
+    fn polygon_cmd(
+        &mut self,
+        points: &[Vec2],
+        color: Color
+    ) {
+        let Some((first, rest)) = points.split_first() else {
+            return;
+        };
+
+        let mut path = Path::default();
+        path.move_to(first.x as f32, first.y as f32);
+
+        for p in rest {
+            path.line_to(p.x as f32, p.y as f32);
+        }
+
+        path.close();
+        self.fill(&mut path, color);
+    }
+}
+
+impl Renderer {
+    #[inline]
+    fn fill(&mut self, path: &mut Path, color: Color) {
+        let paint = Paint::default().with_color(
+            FemtovgColor::rgba(color.r, color.g, color.b, color.a)
+        );
+
+        self.canvas.fill_path(path, &paint);
     }
 }
 
 impl TextSizeHandler for FemtovgTextSizeHandler {
     #[inline]
-    fn text_width(&self, _id: FontId, text: &str) -> i32 {
-        let paint = Paint::default()
-            .with_font(&[self.font_id])
-            .with_font_size(FONT_SIZE_PT);
+    fn text_width(&self, id: FontId, text: &str) -> i32 {
+        let paint = self.fonts.paint(id, 1.);
 
         let metrics = self.ctx.measure_text(0., 0., text, &paint).unwrap();
 
@@ -265,13 +435,35 @@ impl TextSizeHandler for FemtovgTextSizeHandler {
     }
 
     #[inline]
-    fn text_height(&self, _id: FontId) -> i32 {
-        let paint = Paint::default()
-            .with_font(&[self.font_id])
-            .with_font_size(FONT_SIZE_PT);
+    fn text_height(&self, id: FontId) -> i32 {
+        let paint = self.fonts.paint(id, 1.);
 
         let metrics = self.ctx.measure_font(&paint).unwrap();
         
         metrics.height() as i32
     }
 }
+
+impl Fonts {
+    fn get(&self, id: FontId) -> (FemtovgFontId, f32) {
+        let set = self.0.borrow();
+
+        set.fonts.get(&id)
+            .or_else(|| set.fonts.get(&FontId::default()))
+            .copied()
+            .expect("The default font was removed.")
+    }
+
+    /// Femtovg shapes the text with the font and then the fallback fonts,
+    /// but only uses the first 8 of them. The font size is multiplied by `scale`.
+    fn paint(&self, id: FontId, scale: f32) -> Paint {
+        let (font, size) = self.get(id);
+        let fonts: Vec<_> = [font].into_iter()
+            .chain(self.0.borrow().fallbacks.iter().copied())
+            .collect();
+
+        Paint::default()
+            .with_font(&fonts)
+            .with_font_size(size * scale)
+    }
+}
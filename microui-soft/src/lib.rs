@@ -0,0 +1,6 @@
+pub use microui;
+
+pub mod snapshot;
+mod renderer;
+
+pub use renderer::*;
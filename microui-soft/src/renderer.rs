@@ -0,0 +1,782 @@
+use std::{
+    cmp, cell::RefCell, collections::HashMap, f32::consts::TAU, fs::File,
+    io::{BufWriter, Write}, iter, path::Path, rc::Rc
+};
+
+use microui::{
+    Context, CommandHandler, TextSizeHandler,
+    FontId, Icon, IconAtlas, ImageId, UvRect, Angle, Color, Rect, Vec2, rect, text
+};
+
+use ab_glyph::{FontArc, Font, Glyph, InvalidFont, ScaleFont, point};
+
+const DEFAULT_FONT: &[u8] = include_bytes!("../../fonts/ProggyClean.ttf");
+const FONT_SIZE_PT: f32 = 16.0;
+
+/// A CPU renderer that rasterizes the microui command list into
+/// an in-memory RGBA framebuffer. Useful for running without a GPU
+/// or a window, i.e on CI machines.
+pub struct Renderer {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    clip: Rect,
+    fonts: Fonts,
+    images: HashMap<ImageId, Texture>,
+    icon_atlas: Option<IconAtlas>,
+    clear_color: Color,
+    scale_factor: f32
+}
+
+/// Non-premultiplied RGBA pixels of an image.
+struct Texture {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>
+}
+
+/// The faces and sizes of the fonts by their id, shared with the text size handlers
+/// so that text is measured with the font it's drawn with. Unknown ids use the default font.
+#[derive(Clone)]
+struct Fonts(Rc<RefCell<FontSet>>);
+
+struct FontSet {
+    fonts: HashMap<FontId, (FontArc, f32)>,
+    /// Tried in order for the characters that a font doesn't have.
+    fallbacks: Vec<FontArc>
+}
+
+#[derive(Clone)]
+pub struct SoftTextSizeHandler {
+    fonts: Fonts
+}
+
+impl Renderer {
+    pub fn new(width: u32, height: u32) -> Self {
+        let font = FontArc::try_from_slice(DEFAULT_FONT).unwrap();
+        let fonts = HashMap::from([(FontId::default(), (font, FONT_SIZE_PT))]);
+
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+            clip: rect(0, 0, width as i32, height as i32),
+            fonts: Fonts(Rc::new(RefCell::new(FontSet { fonts, fallbacks: Vec::new() }))),
+            images: HashMap::new(),
+            icon_atlas: None,
+            clear_color: Color::rgb(0, 0, 0),
+            scale_factor: 1.
+        }
+    }
+
+    /// Loads a TrueType or OpenType font from `data` to draw text with
+    /// at `size` pixels. Returns the id to draw with, i.e. in
+    /// [`microui::Style::font`] or [`microui::Label::font`].
+    pub fn add_font(&mut self, data: &[u8], size: f32) -> Result<FontId, InvalidFont> {
+        let id = self.fonts.next_id();
+        self.set_font(id, data, size)?;
+
+        Ok(id)
+    }
+
+    /// Creates or replaces the font drawn for `id`. Replacing [`FontId::default`]
+    /// changes the font of everything that doesn't ask for another one.
+    pub fn set_font(&mut self, id: FontId, data: &[u8], size: f32) -> Result<(), InvalidFont> {
+        let font = FontArc::try_from_vec(data.into())?;
+        self.fonts.0.borrow_mut().fonts.insert(id, (font, size));
+
+        Ok(())
+    }
+
+    /// Adds a font that is used for the characters the other fonts don't have,
+    /// i.e. CJK or emoji. Fallback fonts are tried in the order they were added.
+    pub fn add_fallback_font(&mut self, data: &[u8]) -> Result<(), InvalidFont> {
+        let font = FontArc::try_from_vec(data.into())?;
+        self.fonts.0.borrow_mut().fallbacks.push(font);
+
+        Ok(())
+    }
+
+    /// Adds the face of `font` at another size, i.e. for headings.
+    pub fn font_with_size(&mut self, font: FontId, size: f32) -> FontId {
+        let id = self.fonts.next_id();
+        let (face, _) = self.fonts.get(font);
+        self.fonts.0.borrow_mut().fonts.insert(id, (face, size));
+
+        id
+    }
+
+    /// Sets the pixels drawn for `id`. `rgba` must contain
+    /// `width * height` tightly packed, non-premultiplied RGBA pixels.
+    pub fn set_image(&mut self, id: ImageId, width: u32, height: u32, rgba: &[u8]) {
+        assert_eq!(rgba.len(), width as usize * height as usize * 4, "Wrong image size.");
+
+        self.images.insert(id, Texture { width, height, pixels: rgba.into() });
+    }
+
+    #[inline]
+    pub fn remove_image(&mut self, id: ImageId) {
+        self.images.remove(&id);
+    }
+
+    /// Draws the icons in `atlas` from its image instead of the built-in shapes.
+    #[inline]
+    pub fn set_icon_atlas(&mut self, atlas: Option<IconAtlas>) {
+        self.icon_atlas = atlas;
+    }
+
+    /// Draws text `scale_factor` times as large as it's measured by the text size
+    /// handlers. Must be the same as [`Context::set_scale_factor`], which scales the
+    /// measurements to match.
+    #[inline]
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        self.pixels.resize(width as usize * height as usize * 4, 0);
+        self.clip = self.bounds();
+    }
+
+    /// Clears the framebuffer and draws the commands of the last frame.
+    /// Must be called after [`Context::end`].
+    pub fn render(&mut self, ctx: &mut Context, clear_color: Option<Color>) {
+        if let Some(color) = clear_color {
+            self.clear_color = color;
+        }
+
+        self.clear();
+        ctx.handle_commands(self);
+    }
+
+    #[inline]
+    pub fn text_size_handler(&self) -> SoftTextSizeHandler {
+        SoftTextSizeHandler {
+            fonts: self.fonts.clone()
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The framebuffer contents as tightly packed, non-premultiplied RGBA rows.
+    #[inline]
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Panics if the coordinates are outside of the framebuffer.
+    #[inline]
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        assert!(x < self.width && y < self.height, "Pixel out of bounds.");
+
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        let p = &self.pixels[i..i + 4];
+
+        Color::rgba(p[0], p[1], p[2], p[3])
+    }
+
+    pub fn write_png(&self, writer: impl Write) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), png::EncodingError> {
+        let file = File::create(path)?;
+
+        self.write_png(BufWriter::new(file))
+    }
+
+    pub(crate) fn clear(&mut self) {
+        let c = self.clear_color;
+
+        for p in self.pixels.chunks_exact_mut(4) {
+            p.copy_from_slice(&[c.r, c.g, c.b, c.a]);
+        }
+
+        self.clip = self.bounds();
+    }
+
+    #[inline]
+    fn bounds(&self) -> Rect {
+        rect(0, 0, self.width as i32, self.height as i32)
+    }
+
+    fn blend(&mut self, x: i32, y: i32, color: Color, coverage: f32) {
+        if !self.clip.overlaps(Vec2 { x, y }) {
+            return;
+        }
+
+        let alpha = color.a as f32 / 255. * coverage.clamp(0., 1.);
+
+        if alpha <= 0. {
+            return;
+        }
+
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        let dst = &mut self.pixels[i..i + 4];
+
+        if alpha >= 1. {
+            dst.copy_from_slice(&[color.r, color.g, color.b, 255]);
+
+            return;
+        }
+
+        let mix = |src: u8, dst: u8| {
+            (src as f32 * alpha + dst as f32 * (1. - alpha)).round() as u8
+        };
+
+        dst[0] = mix(color.r, dst[0]);
+        dst[1] = mix(color.g, dst[1]);
+        dst[2] = mix(color.b, dst[2]);
+        dst[3] = ((alpha + dst[3] as f32 / 255. * (1. - alpha)) * 255.).round() as u8;
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        let r = rect.intersect(self.clip);
+
+        for y in r.y..r.y + r.h {
+            for x in r.x..r.x + r.w {
+                self.blend(x, y, color, 1.);
+            }
+        }
+    }
+
+    /// Fills every pixel in `area` whose center passes `inside`.
+    fn fill_shape(&mut self, area: Rect, color: Color, inside: impl Fn(f32, f32) -> bool) {
+        let r = area.intersect(self.clip);
+
+        for y in r.y..r.y + r.h {
+            for x in r.x..r.x + r.w {
+                if inside(x as f32 + 0.5, y as f32 + 0.5) {
+                    self.blend(x, y, color, 1.);
+                }
+            }
+        }
+    }
+}
+
+impl CommandHandler for Renderer {
+    #[inline]
+    fn clip_cmd(&mut self, rect: Rect) {
+        self.clip = rect.intersect(self.bounds());
+    }
+
+    #[inline]
+    fn rect_cmd(&mut self, rect: Rect, color: Color) {
+        self.fill_rect(rect, color);
+    }
+
+    fn text_cmd(
+        &mut self,
+        font: FontId,
+        pos: Vec2,
+        color: Color,
+        text: &str
+    ) {
+        let (glyphs, _) = self.fonts.layout(font, text, pos, self.scale_factor);
+
+        for (font, glyph) in glyphs {
+            if let Some(outline) = font.outline_glyph(glyph) {
+                let bounds = outline.px_bounds();
+                let x = bounds.min.x as i32;
+                let y = bounds.min.y as i32;
+
+                outline.draw(|gx, gy, coverage| {
+                    self.blend(x + gx as i32, y + gy as i32, color, coverage);
+                });
+            }
+        }
+    }
+
+    fn icon_cmd(
+        &mut self,
+        id: Icon,
+        rect: Rect,
+        color: Color
+    ) {
+        if let Some(atlas) = &self.icon_atlas {
+            if let Some((dst, uv)) = atlas.locate(id, rect) {
+                self.image_cmd(atlas.image(), dst, uv, color);
+
+                return;
+            }
+        }
+
+        // ProggyClean doesn't contain any icon glyphs so
+        // the icons are drawn as simple shapes instead.
+        let cx = rect.x as f32 + rect.w as f32 / 2.;
+        let cy = rect.y as f32 + rect.h as f32 / 2.;
+        let e = cmp::min(rect.w, rect.h) as f32 / 4.;
+
+        match id {
+            Icon::Close => self.fill_shape(rect, color, |x, y| {
+                let (dx, dy) = (x - cx, y - cy);
+
+                dx.abs() <= e && dy.abs() <= e &&
+                    ((dx - dy).abs() < 1. || (dx + dy).abs() < 1.)
+            }),
+            Icon::Check => {
+                let a = (cx - e, cy);
+                let b = (cx - e / 3., cy + e * 2. / 3.);
+                let c = (cx + e, cy - e * 2. / 3.);
+
+                self.fill_shape(rect, color, |x, y| {
+                    segment_distance((x, y), a, b) < 1. ||
+                        segment_distance((x, y), b, c) < 1.
+                });
+            },
+            Icon::Collapsed => {
+                let tri = [(cx - e / 2., cy - e), (cx + e / 2., cy), (cx - e / 2., cy + e)];
+                self.fill_shape(rect, color, |x, y| in_triangle((x, y), tri));
+            },
+            Icon::Expanded => {
+                let tri = [(cx - e, cy - e / 2.), (cx + e, cy - e / 2.), (cx, cy + e / 2.)];
+                self.fill_shape(rect, color, |x, y| in_triangle((x, y), tri));
+            },
+            Icon::Resize => {
+                let tri = [(cx + e, cy - e), (cx + e, cy + e), (cx - e, cy + e)];
+                self.fill_shape(rect, color, |x, y| in_triangle((x, y), tri));
+            },
+            Icon::None | Icon::Custom(_) => {}
+        }
+    }
+
+    fn image_cmd(
+        &mut self,
+        id: ImageId,
+        rect: Rect,
+        uv: UvRect,
+        tint: Color
+    ) {
+        let Some(image) = self.images.remove(&id) else {
+            return;
+        };
+
+        let r = rect.intersect(self.clip);
+        let mul = |a: u8, b: u8| (a as u32 * b as u32 / 255) as u8;
+
+        // Nearest neighbour sampling of the pixel centers.
+        for y in r.y..r.y + r.h {
+            let v = uv.y + (y - rect.y) as f32 / rect.h as f32 * uv.h + uv.h / rect.h as f32 / 2.;
+            let ty = ((v * image.height as f32) as u32).min(image.height - 1);
+
+            for x in r.x..r.x + r.w {
+                let u = uv.x + (x - rect.x) as f32 / rect.w as f32 * uv.w + uv.w / rect.w as f32 / 2.;
+                let tx = ((u * image.width as f32) as u32).min(image.width - 1);
+
+                let i = (ty as usize * image.width as usize + tx as usize) * 4;
+                let p = &image.pixels[i..i + 4];
+
+                let color = Color::rgba(
+                    mul(p[0], tint.r),
+                    mul(p[1], tint.g),
+                    mul(p[2], tint.b),
+                    mul(p[3], tint.a)
+                );
+
+                self.blend(x, y, color, 1.);
+            }
+        }
+
+        self.images.insert(id, image);
+    }
+
+    fn line_cmd(
+        &mut self,
+        a: Vec2,
+        b: Vec2,
+        thickness: i32,
+        color: Color
+    ) {
+        let h = thickness.max(1) as f32 / 2.;
+        let (ax, ay) = (a.x as f32, a.y as f32);
+        let len = (((b.x - a.x).pow(2) + (b.y - a.y).pow(2)) as f32).sqrt().max(f32::EPSILON);
+        let (dx, dy) = ((b.x - a.x) as f32 / len, (b.y - a.y) as f32 / len);
+
+        let area = Rect::bounding([a, b]).expand(thickness / 2 + 1);
+
+        self.fill_shape(area, color, |x, y| {
+            let along = (x - ax) * dx + (y - ay) * dy;
+            let across = ((x - ax) * dy - (y - ay) * dx).abs();
+
+            along >= -h && along <= len + h && across <= h
+        });
+    }
+
+    fn rounded_rect_cmd(
+        &mut self,
+        rect: Rect,
+        radius: i32,
+        color: Color
+    ) {
+        let r = radius.clamp(0, cmp::min(rect.w, rect.h) / 2) as f32;
+        let (x0, y0) = (rect.x as f32 + r, rect.y as f32 + r);
+        let (x1, y1) = ((rect.x + rect.w) as f32 - r, (rect.y + rect.h) as f32 - r);
+
+        self.fill_shape(rect, color, |x, y| {
+            let (cx, cy) = (x.clamp(x0, x1), y.clamp(y0, y1));
+
+            (x - cx).powi(2) + (y - cy).powi(2) <= r * r
+        });
+    }
+
+    fn circle_cmd(
+        &mut self,
+        center: Vec2,
+        radius: i32,
+        color: Color
+    ) {
+        let area = rect(center.x - radius, center.y - radius, radius * 2, radius * 2);
+        let (cx, cy, r) = (center.x as f32, center.y as f32, radius as f32);
+
+        self.fill_shape(area, color, |x, y| (x - cx).powi(2) + (y - cy).powi(2) <= r * r);
+    }
+
+    fn arc_cmd(
+        &mut self,
+        center: Vec2,
+        radius: i32,
+        start: Angle,
+        end: Angle,
+        thickness: i32,
+        color: Color
+    ) {
+        let h = thickness.max(1) as f32 / 2.;
+        let (start, sweep) = if end.0 < start.0 {
+            (end.0, start.0 - end.0)
+        } else {
+            (start.0, end.0 - start.0)
+        };
+
+        let extent = radius + thickness / 2 + 1;
+        let area = rect(center.x - extent, center.y - extent, extent * 2, extent * 2);
+        let (cx, cy, r) = (center.x as f32, center.y as f32, radius as f32);
+
+        self.fill_shape(area, color, |x, y| {
+            let (dx, dy) = (x - cx, y - cy);
+            let angle = (dy.atan2(dx) - start).rem_euclid(TAU);
+
+            ((dx * dx + dy * dy).sqrt() - r).abs() <= h && (sweep >= TAU || angle <= sweep)
+        });
+    }
+
+    fn triangle_cmd(
+        &mut self,
+        points: [Vec2; 3],
+        color: Color
+    ) {
+        let tri = points.map(|p| (p.x as f32, p.y as f32));
+
+        self.fill_shape(Rect::bounding(points), color, |x, y| in_triangle((x, y), tri));
+    }
+
+    fn polygon_cmd(
+        &mut self,
+        points: &[Vec2],
+        color: Color
+    ) {
+        let area = Rect::bounding(points.iter().copied());
+
+        self.fill_shape(area, color, |x, y| in_polygon((x, y), points));
+    }
+}
+
+impl TextSizeHandler for SoftTextSizeHandler {
+    #[inline]
+    fn text_width(&self, id: FontId, text: &str) -> i32 {
+        self.fonts.layout(id, text, Vec2::ZERO, 1.).1 as i32
+    }
+
+    #[inline]
+    fn text_height(&self, id: FontId) -> i32 {
+        let (font, size) = self.fonts.get(id);
+
+        font.as_scaled(size).height() as i32
+    }
+}
+
+impl Fonts {
+    fn get(&self, id: FontId) -> (FontArc, f32) {
+        let set = self.0.borrow();
+
+        set.fonts.get(&id)
+            .or_else(|| set.fonts.get(&FontId::default()))
+            .cloned()
+            .expect("The default font was removed.")
+    }
+
+    fn next_id(&self) -> FontId {
+        FontId(self.0.borrow().fonts.keys().map(|id| id.0 + 1).max().unwrap_or(0))
+    }
+
+    /// Places the glyphs of `text` in the order they are drawn, starting with the
+    /// top left corner at `pos`. Every character is taken from the first of the font
+    /// and the fallback fonts that has it. The font size is multiplied by `scale`.
+    /// Returns the glyphs with their font and the width.
+    fn layout(&self, id: FontId, text: &str, pos: Vec2, scale: f32) -> (Vec<(FontArc, Glyph)>, f32) {
+        let (font, size) = self.get(id);
+        let size = size * scale;
+        let set = self.0.borrow();
+
+        let mut caret = point(pos.x as f32, pos.y as f32 + font.as_scaled(size).ascent());
+        let mut last = None;
+        let mut glyphs = Vec::new();
+
+        for c in text::visual_order(text).chars() {
+            let (index, face) = iter::once(&font)
+                .chain(&set.fallbacks)
+                .enumerate()
+                .find(|(_, face)| face.glyph_id(c).0 != 0)
+                .unwrap_or((0, &font));
+
+            let face = face.as_scaled(size);
+            let id = face.glyph_id(c);
+
+            // Kerning only applies between glyphs of the same font.
+            if let Some((last_index, last_id)) = last {
+                if last_index == index {
+                    caret.x += face.kern(last_id, id);
+                }
+            }
+
+            last = Some((index, id));
+
+            glyphs.push((face.font.clone(), id.with_scale_and_position(size, caret)));
+            caret.x += face.h_advance(id);
+        }
+
+        (glyphs, caret.x - pos.x as f32)
+    }
+}
+
+type Point = (f32, f32);
+
+fn in_triangle(p: Point, [a, b, c]: [Point; 3]) -> bool {
+    let edge = |a: Point, b: Point| (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0);
+
+    let d1 = edge(a, b);
+    let d2 = edge(b, c);
+    let d3 = edge(c, a);
+
+    let has_neg = d1 < 0. || d2 < 0. || d3 < 0.;
+    let has_pos = d1 > 0. || d2 > 0. || d3 > 0.;
+
+    !(has_neg && has_pos)
+}
+
+/// Uses the even-odd rule.
+fn in_polygon(p: Point, points: &[Vec2]) -> bool {
+    let mut inside = false;
+
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        let (ax, ay, bx, by) = (a.x as f32, a.y as f32, b.x as f32, b.y as f32);
+
+        if (ay <= p.1) != (by <= p.1) && p.0 < ax + (p.1 - ay) * (bx - ax) / (by - ay) {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+fn segment_distance(p: Point, a: Point, b: Point) -> f32 {
+    let (abx, aby) = (b.0 - a.0, b.1 - a.1);
+    let len = abx * abx + aby * aby;
+
+    let t = if len > 0. {
+        (((p.0 - a.0) * abx + (p.1 - a.1) * aby) / len).clamp(0., 1.)
+    } else {
+        0.
+    };
+
+    let (x, y) = (a.0 + abx * t, a.1 + aby * t);
+
+    ((p.0 - x).powi(2) + (p.1 - y).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use microui::{Window, WidgetColor};
+    use super::*;
+
+    #[test]
+    fn rect_and_clip() {
+        let mut renderer = Renderer::new(16, 16);
+        renderer.clear();
+
+        renderer.clip_cmd(rect(0, 0, 8, 16));
+        renderer.rect_cmd(rect(4, 4, 8, 8), Color::rgb(255, 0, 0));
+
+        assert_eq!(renderer.pixel(4, 4), Color::rgb(255, 0, 0));
+        assert_eq!(renderer.pixel(7, 11), Color::rgb(255, 0, 0));
+        assert_eq!(renderer.pixel(8, 4), Color::rgb(0, 0, 0));
+        assert_eq!(renderer.pixel(3, 3), Color::rgb(0, 0, 0));
+    }
+
+    #[test]
+    fn render_frame() {
+        let mut renderer = Renderer::new(200, 200);
+        let mut ctx = Context::new(renderer.text_size_handler());
+
+        ctx.begin();
+        Window::new("Test", rect(10, 10, 100, 100)).show(&mut ctx, |ctx| {
+            ctx.label("Hello");
+        });
+        ctx.end();
+
+        renderer.render(&mut ctx, Some(Color::rgb(1, 2, 3)));
+
+        assert_eq!(renderer.pixel(0, 0), Color::rgb(1, 2, 3));
+        assert_eq!(renderer.pixel(50, 90), ctx.style.colors[WidgetColor::WindowBackground]);
+
+        let mut png = Vec::new();
+        renderer.write_png(&mut png).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+
+        assert!(renderer.text_size_handler().text_width(FontId::default(), "Hello") > 0);
+    }
+
+    #[test]
+    fn fonts() {
+        let mut renderer = Renderer::new(64, 64);
+        let handler = renderer.text_size_handler();
+
+        let large = renderer.add_font(DEFAULT_FONT, FONT_SIZE_PT * 2.).unwrap();
+        let small = renderer.font_with_size(FontId::default(), FONT_SIZE_PT / 2.);
+        assert_eq!((large, small), (FontId(1), FontId(2)));
+        assert!(renderer.add_font(&[1, 2, 3], 12.).is_err());
+
+        // Handlers created before the fonts were added know about them too.
+        let width = handler.text_width(FontId::default(), "Hello");
+        assert_eq!(handler.text_width(large, "Hello"), width * 2);
+        assert_eq!(handler.text_width(small, "Hello"), width / 2);
+        assert_eq!(handler.text_height(large), handler.text_height(FontId::default()) * 2);
+
+        // Unknown fonts fall back to the default one.
+        assert_eq!(handler.text_width(FontId(9), "Hello"), width);
+
+        let white = Color::rgb(255, 255, 255);
+        let lit = |renderer: &Renderer| (0..64)
+            .flat_map(|y| (0..64).map(move |x| (x, y)))
+            .filter(|&(x, y)| renderer.pixel(x, y) != Color::rgb(0, 0, 0))
+            .map(|(_, y)| y)
+            .max();
+
+        renderer.clear();
+        renderer.text_cmd(FontId::default(), Vec2 { x: 0, y: 0 }, white, "H");
+        let default_bottom = lit(&renderer).unwrap();
+
+        renderer.clear();
+        renderer.text_cmd(large, Vec2 { x: 0, y: 0 }, white, "H");
+        let large_bottom = lit(&renderer).unwrap();
+        assert!(large_bottom > default_bottom);
+
+        // Text is drawn at the scale factor without changing its measurements.
+        renderer.set_scale_factor(2.);
+        renderer.clear();
+        renderer.text_cmd(FontId::default(), Vec2 { x: 0, y: 0 }, white, "H");
+        assert_eq!(lit(&renderer).unwrap(), large_bottom);
+        assert_eq!(handler.text_width(FontId::default(), "Hello"), width);
+    }
+
+    #[test]
+    fn fallback_fonts() {
+        let mut renderer = Renderer::new(8, 8);
+        let handler = renderer.text_size_handler();
+
+        // Characters that no font has are drawn with the missing glyph of the font itself.
+        let missing = handler.text_width(FontId::default(), "日");
+        assert!(missing > 0);
+
+        assert!(renderer.add_fallback_font(&[1, 2, 3]).is_err());
+        renderer.add_fallback_font(DEFAULT_FONT).unwrap();
+        assert_eq!(handler.text_width(FontId::default(), "日"), missing);
+    }
+
+    #[test]
+    fn image() {
+        let mut renderer = Renderer::new(8, 8);
+        renderer.clear();
+
+        // A 2x1 image with a red and a green pixel.
+        renderer.set_image(ImageId(1), 2, 1, &[255, 0, 0, 255, 0, 255, 0, 255]);
+
+        renderer.image_cmd(ImageId(1), rect(0, 0, 4, 4), UvRect::FULL, Color::rgb(255, 255, 255));
+        assert_eq!(renderer.pixel(1, 3), Color::rgb(255, 0, 0));
+        assert_eq!(renderer.pixel(2, 0), Color::rgb(0, 255, 0));
+
+        let right = UvRect { x: 0.5, y: 0., w: 0.5, h: 1. };
+        renderer.image_cmd(ImageId(1), rect(4, 4, 4, 4), right, Color::rgb(255, 255, 0));
+        assert_eq!(renderer.pixel(4, 4), Color::rgb(0, 255, 0));
+
+        // Unknown images are ignored.
+        renderer.image_cmd(ImageId(2), rect(0, 4, 4, 4), UvRect::FULL, Color::rgb(255, 255, 255));
+        assert_eq!(renderer.pixel(0, 4), Color::rgb(0, 0, 0));
+    }
+
+    #[test]
+    fn shapes() {
+        let mut renderer = Renderer::new(32, 32);
+        renderer.clear();
+
+        let red = Color::rgb(255, 0, 0);
+        renderer.circle_cmd(Vec2 { x: 16, y: 16 }, 8, red);
+        assert_eq!(renderer.pixel(16, 16), red);
+        assert_eq!(renderer.pixel(9, 9), Color::rgb(0, 0, 0));
+
+        renderer.clear();
+        renderer.rounded_rect_cmd(rect(0, 0, 16, 16), 6, red);
+        assert_eq!(renderer.pixel(8, 0), red);
+        assert_eq!(renderer.pixel(0, 0), Color::rgb(0, 0, 0));
+
+        renderer.clear();
+        renderer.line_cmd(Vec2 { x: 0, y: 20 }, Vec2 { x: 31, y: 20 }, 2, red);
+        assert_eq!(renderer.pixel(10, 20), red);
+        assert_eq!(renderer.pixel(10, 22), Color::rgb(0, 0, 0));
+
+        // The right half of a ring.
+        renderer.clear();
+        renderer.arc_cmd(Vec2 { x: 16, y: 16 }, 10, Angle::degrees(-90.), Angle::degrees(90.), 2, red);
+        assert_eq!(renderer.pixel(26, 16), red);
+        assert_eq!(renderer.pixel(6, 16), Color::rgb(0, 0, 0));
+
+        renderer.clear();
+        renderer.polygon_cmd(&[Vec2 { x: 0, y: 0 }, Vec2 { x: 8, y: 0 }, Vec2 { x: 8, y: 8 }, Vec2 { x: 0, y: 8 }], red);
+        assert_eq!(renderer.pixel(4, 4), red);
+        assert_eq!(renderer.pixel(9, 4), Color::rgb(0, 0, 0));
+    }
+
+    #[test]
+    fn icon_atlas() {
+        let mut renderer = Renderer::new(8, 8);
+        renderer.clear();
+
+        // A white 2x2 icon that is centered in the rect.
+        renderer.set_image(ImageId(1), 2, 2, &[255; 16]);
+        renderer.set_icon_atlas(Some(IconAtlas::new(ImageId(1), 2, 2).icon(Icon::Custom(0), rect(0, 0, 2, 2))));
+
+        renderer.icon_cmd(Icon::Custom(0), rect(0, 0, 8, 8), Color::rgb(0, 0, 255));
+        assert_eq!(renderer.pixel(4, 4), Color::rgb(0, 0, 255));
+        assert_eq!(renderer.pixel(2, 2), Color::rgb(0, 0, 0));
+
+        // Icons that aren't in the atlas are still drawn.
+        renderer.clear();
+        renderer.icon_cmd(Icon::Resize, rect(0, 0, 8, 8), Color::rgb(0, 255, 0));
+        assert!(renderer.pixels().chunks_exact(4).any(|p| p[1] == 255));
+    }
+}
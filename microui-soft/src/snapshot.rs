@@ -0,0 +1,614 @@
+//! Test support for comparing frames against checked-in fixtures.
+//!
+//! A [`Snapshot`] is the command stream of a single frame in a stable,
+//! line-based text format. Fixtures are (re)generated by running the tests
+//! with the `MICROUI_UPDATE_SNAPSHOTS` environment variable set.
+
+use std::{env, fmt, fs, path::Path};
+
+use microui::{
+    Context, CommandHandler, FontId, Icon, ImageId, UvRect,
+    Angle, Color, Rect, Vec2, InputRecording, InputPlayer
+};
+
+use crate::Renderer;
+
+pub const UPDATE_ENV_VAR: &str = "MICROUI_UPDATE_SNAPSHOTS";
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum RecordedCommand {
+    Clip(Rect),
+    Rect {
+        rect: Rect,
+        color: Color
+    },
+    Text {
+        font: FontId,
+        pos: Vec2,
+        color: Color,
+        text: String
+    },
+    Icon {
+        id: Icon,
+        rect: Rect,
+        color: Color
+    },
+    Image {
+        id: ImageId,
+        rect: Rect,
+        uv: UvRect,
+        tint: Color
+    },
+    Line {
+        a: Vec2,
+        b: Vec2,
+        thickness: i32,
+        color: Color
+    },
+    RoundedRect {
+        rect: Rect,
+        radius: i32,
+        color: Color
+    },
+    Circle {
+        center: Vec2,
+        radius: i32,
+        color: Color
+    },
+    Arc {
+        center: Vec2,
+        radius: i32,
+        start: Angle,
+        end: Angle,
+        thickness: i32,
+        color: Color
+    },
+    Triangle {
+        points: [Vec2; 3],
+        color: Color
+    },
+    Polygon {
+        points: Vec<Vec2>,
+        color: Color
+    }
+}
+
+/// The commands emitted by [`Context::handle_commands`] for one frame.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Snapshot {
+    commands: Vec<RecordedCommand>
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Mismatch {
+    pub index: usize,
+    pub expected: Option<String>,
+    pub actual: Option<String>
+}
+
+/// Drives a [`Context`] headlessly, recording and rendering every frame.
+pub struct Harness {
+    pub ctx: Box<Context>,
+    renderer: Renderer
+}
+
+impl Harness {
+    pub fn new(width: u32, height: u32) -> Self {
+        let renderer = Renderer::new(width, height);
+        let ctx = Context::new(renderer.text_size_handler());
+
+        Self { ctx, renderer }
+    }
+
+    /// Runs `contents` between [`Context::begin`] and [`Context::end`],
+    /// renders the result and returns the recorded commands.
+    pub fn frame(&mut self, contents: impl FnOnce(&mut Context)) -> Snapshot {
+        self.ctx.begin();
+        contents(&mut self.ctx);
+        self.ctx.end();
+
+        let mut snapshot = Snapshot::default();
+        self.ctx.handle_commands(&mut snapshot);
+
+        self.renderer.clear();
+        snapshot.replay(&mut self.renderer);
+
+        snapshot
+    }
+
+    /// Runs frames with `contents` until all of the recorded input has been
+    /// fed to the context and returns the commands of the last frame.
+    pub fn replay(
+        &mut self,
+        recording: InputRecording,
+        mut contents: impl FnMut(&mut Context)
+    ) -> Snapshot {
+        let mut player = InputPlayer::new(recording);
+
+        loop {
+            player.play(&mut self.ctx);
+            let snapshot = self.frame(&mut contents);
+
+            if player.is_finished() {
+                return snapshot;
+            }
+        }
+    }
+
+    #[inline]
+    pub fn renderer(&self) -> &Renderer {
+        &self.renderer
+    }
+
+    /// Allows setting the images drawn by the renderer.
+    #[inline]
+    pub fn renderer_mut(&mut self) -> &mut Renderer {
+        &mut self.renderer
+    }
+}
+
+impl Snapshot {
+    #[inline]
+    pub fn commands(&self) -> &[RecordedCommand] {
+        &self.commands
+    }
+
+    /// Feeds the recorded commands to another handler in the same order.
+    pub fn replay(&self, handler: &mut impl CommandHandler) {
+        for cmd in &self.commands {
+            match cmd {
+                RecordedCommand::Clip(rect) => handler.clip_cmd(*rect),
+                RecordedCommand::Rect { rect, color } => handler.rect_cmd(*rect, *color),
+                RecordedCommand::Icon { id, rect, color } => handler.icon_cmd(*id, *rect, *color),
+                RecordedCommand::Image { id, rect, uv, tint } => {
+                    handler.image_cmd(*id, *rect, *uv, *tint)
+                }
+                RecordedCommand::Line { a, b, thickness, color } => {
+                    handler.line_cmd(*a, *b, *thickness, *color)
+                }
+                RecordedCommand::RoundedRect { rect, radius, color } => {
+                    handler.rounded_rect_cmd(*rect, *radius, *color)
+                }
+                RecordedCommand::Circle { center, radius, color } => {
+                    handler.circle_cmd(*center, *radius, *color)
+                }
+                RecordedCommand::Arc { center, radius, start, end, thickness, color } => {
+                    handler.arc_cmd(*center, *radius, *start, *end, *thickness, *color)
+                }
+                RecordedCommand::Triangle { points, color } => handler.triangle_cmd(*points, *color),
+                RecordedCommand::Polygon { points, color } => handler.polygon_cmd(points, *color),
+                RecordedCommand::Text { font, pos, color, text } => {
+                    handler.text_cmd(*font, *pos, *color, text)
+                }
+            }
+        }
+    }
+
+    /// Compares the commands line by line against the serialized `expected` fixture.
+    pub fn diff(&self, expected: &str) -> Vec<Mismatch> {
+        let actual: Vec<String> = self.commands.iter().map(|x| x.to_string()).collect();
+        let expected: Vec<&str> = expected.lines().collect();
+
+        let len = actual.len().max(expected.len());
+        let mut mismatches = Vec::new();
+
+        for index in 0..len {
+            let a = actual.get(index).map(String::as_str);
+            let e = expected.get(index).copied();
+
+            if a != e {
+                mismatches.push(Mismatch {
+                    index,
+                    expected: e.map(Into::into),
+                    actual: a.map(Into::into)
+                });
+            }
+        }
+
+        mismatches
+    }
+
+    /// Panics with a report of every mismatched command if this snapshot doesn't
+    /// match the fixture at `path`. The fixture is written instead if
+    /// [`UPDATE_ENV_VAR`] is set.
+    pub fn assert_matches(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+
+        if should_update(path) {
+            fs::write(path, self.to_string()).unwrap();
+
+            return;
+        }
+
+        let expected = fs::read_to_string(path).unwrap();
+        let mismatches = self.diff(&expected);
+
+        if !mismatches.is_empty() {
+            let mut report = format!(
+                "Snapshot {} has {} mismatched command(s):\n",
+                path.display(),
+                mismatches.len()
+            );
+
+            for m in &mismatches {
+                report.push_str(&format!("{}\n", m));
+            }
+
+            panic!("{}", report);
+        }
+    }
+}
+
+/// Whether the fixture at `path` should be (re)written, creating its directory
+/// if so. A missing fixture is an error unless [`UPDATE_ENV_VAR`] is set, so
+/// that a typo in the path doesn't make the test pass.
+fn should_update(path: &Path) -> bool {
+    if env::var_os(UPDATE_ENV_VAR).is_none() {
+        assert!(
+            path.exists(),
+            "Fixture {} doesn't exist. Run the tests with {} set to create it.",
+            path.display(),
+            UPDATE_ENV_VAR
+        );
+
+        return false;
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+
+    true
+}
+
+/// Panics if the framebuffer of `renderer` doesn't match the PNG at `path`.
+/// The image is written instead if [`UPDATE_ENV_VAR`] is set.
+pub fn assert_image_matches(renderer: &Renderer, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+
+    if should_update(path) {
+        renderer.save_png(path).unwrap();
+
+        return;
+    }
+
+    let decoder = png::Decoder::new(fs::File::open(path).unwrap());
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+
+    assert!(
+        info.width == renderer.width() && info.height == renderer.height(),
+        "Image {} is {}x{} but the framebuffer is {}x{}.",
+        path.display(),
+        info.width,
+        info.height,
+        renderer.width(),
+        renderer.height()
+    );
+
+    let mut diff_count = 0;
+    let mut first = None;
+
+    for (i, (a, e)) in renderer.pixels()
+        .chunks_exact(4)
+        .zip(pixels[..info.buffer_size()].chunks_exact(4))
+        .enumerate()
+    {
+        if a != e {
+            diff_count += 1;
+            first.get_or_insert(i);
+        }
+    }
+
+    if let Some(i) = first {
+        let x = i as u32 % info.width;
+        let y = i as u32 / info.width;
+
+        panic!(
+            "Image {} has {} mismatched pixel(s), first at {}, {}: expected {:?}, got {:?}.",
+            path.display(),
+            diff_count,
+            x,
+            y,
+            &pixels[i * 4..i * 4 + 4],
+            renderer.pixel(x, y)
+        );
+    }
+}
+
+impl CommandHandler for Snapshot {
+    #[inline]
+    fn clip_cmd(&mut self, rect: Rect) {
+        self.commands.push(RecordedCommand::Clip(rect));
+    }
+
+    #[inline]
+    fn rect_cmd(&mut self, rect: Rect, color: Color) {
+        self.commands.push(RecordedCommand::Rect { rect, color });
+    }
+
+    #[inline]
+    fn text_cmd(
+        &mut self,
+        font: FontId,
+        pos: Vec2,
+        color: Color,
+        text: &str
+    ) {
+        self.commands.push(RecordedCommand::Text { font, pos, color, text: text.into() });
+    }
+
+    #[inline]
+    fn icon_cmd(
+        &mut self,
+        id: Icon,
+        rect: Rect,
+        color: Color
+    ) {
+        self.commands.push(RecordedCommand::Icon { id, rect, color });
+    }
+
+    #[inline]
+    fn image_cmd(
+        &mut self,
+        id: ImageId,
+        rect: Rect,
+        uv: UvRect,
+        tint: Color
+    ) {
+        self.commands.push(RecordedCommand::Image { id, rect, uv, tint });
+    }
+
+    #[inline]
+    fn line_cmd(
+        &mut self,
+        a: Vec2,
+        b: Vec2,
+        thickness: i32,
+        color: Color
+    ) {
+        self.commands.push(RecordedCommand::Line { a, b, thickness, color });
+    }
+
+    #[inline]
+    fn rounded_rect_cmd(
+        &mut self,
+        rect: Rect,
+        radius: i32,
+        color: Color
+    ) {
+        self.commands.push(RecordedCommand::RoundedRect { rect, radius, color });
+    }
+
+    #[inline]
+    fn circle_cmd(
+        &mut self,
+        center: Vec2,
+        radius: i32,
+        color: Color
+    ) {
+        self.commands.push(RecordedCommand::Circle { center, radius, color });
+    }
+
+    #[inline]
+    fn arc_cmd(
+        &mut self,
+        center: Vec2,
+        radius: i32,
+        start: Angle,
+        end: Angle,
+        thickness: i32,
+        color: Color
+    ) {
+        self.commands.push(RecordedCommand::Arc { center, radius, start, end, thickness, color });
+    }
+
+    #[inline]
+    fn triangle_cmd(
+        &mut self,
+        points: [Vec2; 3],
+        color: Color
+    ) {
+        self.commands.push(RecordedCommand::Triangle { points, color });
+    }
+
+    #[inline]
+    fn polygon_cmd(
+        &mut self,
+        points: &[Vec2],
+        color: Color
+    ) {
+        self.commands.push(RecordedCommand::Polygon { points: points.into(), color });
+    }
+}
+
+impl fmt::Display for RecordedCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let r = |r: &Rect| format!("{} {} {} {}", r.x, r.y, r.w, r.h);
+        let c = |c: &Color| format!("#{:02x}{:02x}{:02x}{:02x}", c.r, c.g, c.b, c.a);
+        let p = |p: &[Vec2]| p.iter().map(|p| format!("{} {}", p.x, p.y)).collect::<Vec<_>>().join(" ");
+
+        match self {
+            Self::Clip(rect) => write!(f, "clip {}", r(rect)),
+            Self::Rect { rect, color } => write!(f, "rect {} {}", r(rect), c(color)),
+            Self::Icon { id, rect, color } => write!(f, "icon {:?} {} {}", id, r(rect), c(color)),
+            Self::Image { id, rect, uv, tint } => write!(
+                f,
+                "image {} {} {} {} {} {} {}",
+                id.0,
+                r(rect),
+                uv.x,
+                uv.y,
+                uv.w,
+                uv.h,
+                c(tint)
+            ),
+            Self::Line { a, b, thickness, color } => write!(
+                f,
+                "line {} {} {} {} {} {}",
+                a.x,
+                a.y,
+                b.x,
+                b.y,
+                thickness,
+                c(color)
+            ),
+            Self::RoundedRect { rect, radius, color } => {
+                write!(f, "rounded_rect {} {} {}", r(rect), radius, c(color))
+            },
+            Self::Circle { center, radius, color } => {
+                write!(f, "circle {} {} {} {}", center.x, center.y, radius, c(color))
+            },
+            Self::Arc { center, radius, start, end, thickness, color } => write!(
+                f,
+                "arc {} {} {} {} {} {} {}",
+                center.x,
+                center.y,
+                radius,
+                start.0,
+                end.0,
+                thickness,
+                c(color)
+            ),
+            Self::Triangle { points, color } => write!(f, "triangle {} {}", p(points), c(color)),
+            Self::Polygon { points, color } => write!(f, "polygon {} {}", p(points), c(color)),
+            Self::Text { font, pos, color, text } => write!(
+                f,
+                "text {} {} {} {} {:?}",
+                font.0,
+                pos.x,
+                pos.y,
+                c(color),
+                text
+            )
+        }
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for cmd in &self.commands {
+            writeln!(f, "{}", cmd)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let missing = "<none>";
+
+        write!(
+            f,
+            "  #{}: expected `{}`, got `{}`",
+            self.index,
+            self.expected.as_deref().unwrap_or(missing),
+            self.actual.as_deref().unwrap_or(missing)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use microui::{Window, MouseButton, rect, vec2};
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/snapshots/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    fn window(ctx: &mut Context, clicked: &mut bool) {
+        Window::new("Snapshot", rect(10, 10, 200, 120)).show(ctx, |ctx| {
+            ctx.label("Label");
+
+            if ctx.button("Button") {
+                *clicked = true;
+            }
+        });
+    }
+
+    #[test]
+    fn window_fixture() {
+        let mut harness = Harness::new(240, 160);
+        let mut clicked = false;
+
+        harness.frame(|ctx| window(ctx, &mut clicked));
+
+        // The hover root is only updated at the start of the next
+        // frame so we need two frames for the button to become hovered.
+        harness.ctx.input_mouse_move(vec2(50, 70));
+        harness.frame(|ctx| window(ctx, &mut clicked));
+        harness.frame(|ctx| window(ctx, &mut clicked));
+
+        harness.ctx.input_mouse_down(vec2(50, 70), MouseButton::Left);
+        let snapshot = harness.frame(|ctx| window(ctx, &mut clicked));
+
+        assert!(clicked);
+        snapshot.assert_matches(fixture("window.txt"));
+        assert_image_matches(harness.renderer(), fixture("window.png"));
+    }
+
+    #[test]
+    fn replay_matches_live_input() {
+        let mut live = Harness::new(240, 160);
+        let mut clicked = false;
+
+        live.frame(|ctx| window(ctx, &mut clicked));
+        live.ctx.start_recording();
+
+        live.ctx.input_mouse_move(vec2(50, 70));
+        live.frame(|ctx| window(ctx, &mut clicked));
+        live.frame(|ctx| window(ctx, &mut clicked));
+        live.ctx.input_mouse_down(vec2(50, 70), MouseButton::Left);
+        let expected = live.frame(|ctx| window(ctx, &mut clicked));
+
+        let recording = live.ctx.stop_recording().unwrap();
+        let mut replayed = Harness::new(240, 160);
+        let mut replay_clicked = false;
+
+        replayed.frame(|ctx| window(ctx, &mut replay_clicked));
+        let snapshot = replayed.replay(recording, |ctx| window(ctx, &mut replay_clicked));
+
+        assert!(clicked && replay_clicked);
+        assert_eq!(snapshot, expected);
+    }
+
+    #[test]
+    fn diff_reports_mismatches() {
+        let mut harness = Harness::new(240, 160);
+        let snapshot = harness.frame(|ctx| window(ctx, &mut false));
+
+        let mut expected: Vec<String> = snapshot.commands().iter().map(|x| x.to_string()).collect();
+        expected[1] = "rect 0 0 1 1 #000000ff".into();
+        expected.push("clip 0 0 1 1".into());
+
+        let mismatches = snapshot.diff(&expected.join("\n"));
+
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].index, 1);
+        assert_eq!(mismatches[0].actual, Some(snapshot.commands()[1].to_string()));
+        assert_eq!(mismatches[1].index, expected.len() - 1);
+        assert_eq!(mismatches[1].actual, None);
+    }
+
+    #[test]
+    fn missing_fixture() {
+        if env::var_os(UPDATE_ENV_VAR).is_some() {
+            return;
+        }
+
+        let mut harness = Harness::new(240, 160);
+        let snapshot = harness.frame(|ctx| window(ctx, &mut false));
+        let path = fixture("missing.txt");
+
+        let message = std::panic::catch_unwind(|| snapshot.assert_matches(&path))
+            .unwrap_err()
+            .downcast::<String>()
+            .unwrap();
+
+        assert!(message.contains(&path) && message.contains(UPDATE_ENV_VAR));
+        assert!(!Path::new(&path).exists());
+    }
+}
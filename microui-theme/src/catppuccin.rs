@@ -1,4 +1,4 @@
-use microui::{Color, WidgetColors, WidgetColor};
+use microui::{Color, WidgetColors, WidgetColor, Style, Shadow, vec2};
 
 /// The colors for a theme variant.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -148,6 +148,18 @@ pub const MOCHA: Theme = Theme {
 };
 
 impl Theme {
+    /// Sets the colors and gives windows and widgets rounded corners and a soft shadow.
+    pub fn apply(&self, style: &mut Style) {
+        style.colors = self.widget_colors();
+        style.corner_radius = 4;
+        style.border_width = 1;
+        style.shadow = Some(Shadow {
+            offset: vec2(0, 3),
+            blur: 6,
+            color: Color::rgba(self.crust.r, self.crust.g, self.crust.b, 120)
+        });
+    }
+
     pub fn widget_colors(&self) -> WidgetColors {
         use WidgetColor::*;
 
@@ -167,6 +179,10 @@ impl Theme {
         c[BaseFocus] = self.surface2;
         c[ScrollBase] = self.surface1;
         c[ScrollThumb] = self.overlay0;
+        c[TextSelection] = Color::rgba(self.blue.r, self.blue.g, self.blue.b, 96);
+        c[FocusRing] = self.lavender;
+        c[DockPreview] = Color::rgba(self.lavender.r, self.lavender.g, self.lavender.b, 80);
+        c[TextDisabled] = self.overlay1;
 
         c
     }
@@ -0,0 +1,34 @@
+struct VertexOutput {
+    @location(0) uv: vec2<f32>,
+    @location(1) tint: vec4<f32>,
+    @builtin(position) clip_position: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> u_screen_size: vec2<f32>;
+@group(1) @binding(0) var t_image: texture_2d<f32>;
+@group(1) @binding(1) var s_image: sampler;
+
+@vertex
+fn vs_main(
+    @location(0) pos: vec2<i32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) tint: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    out.uv = uv;
+    out.tint = tint;
+    out.clip_position = vec4<f32>(
+        2.0 * f32(pos.x) / u_screen_size.x - 1.0,
+        1.0 - 2.0 * f32(pos.y) / u_screen_size.y,
+        0.5,
+        1.0,
+    );
+
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_image, s_image, in.uv) * in.tint;
+}
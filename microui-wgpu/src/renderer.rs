@@ -1,10 +1,13 @@
-use std::rc::Rc;
+use std::{
+    rc::Rc, cell::RefCell, collections::HashMap, error::Error, mem, ops::Range,
+    num::{NonZeroU32, NonZeroU64}, f32::consts::{PI, TAU}
+};
 
 use microui_app::{
     MicrouiRenderer,
     microui::{
         Context, CommandHandler, TextSizeHandler,
-        FontId, Icon, Color, Rect, Vec2
+        FontId, Icon, IconAtlas, ImageId, UvRect, Angle, Color, Rect, Vec2, text
     },
     winit::{
         window::{WindowBuilder, Window},
@@ -13,20 +16,20 @@ use microui_app::{
     }
 };
 
-use wgpu::util::StagingBelt;
+use wgpu::util::{StagingBelt, DeviceExt};
 use wgpu_glyph::{
-    GlyphBrush, Section, Text, Region,
+    GlyphBrush, GlyphBrushBuilder, Section, Text, Region,
     FontId as GlyphBrushFontId, ab_glyph::{FontArc, Font, ScaleFont},
     orthographic_projection
 };
 
 use bytemuck::{Pod, Zeroable};
-//use pollster::FutureExt;
+use pollster::FutureExt;
 
-//const DEFAULT_FONT: &[u8] = include_bytes!("../../fonts/ProggyClean.ttf");
+const DEFAULT_FONT: &[u8] = include_bytes!("../../fonts/ProggyClean.ttf");
 const FONT_SIZE_PT: f32 = 16.0;
-//const INDEX_BUFFER_INITIAL_LEN: u64 = 1024;
-//const VERTEX_BUFFER_INITIAL_LEN: u64 = 512;
+const INDEX_BUFFER_INITIAL_LEN: u64 = 1024;
+const VERTEX_BUFFER_INITIAL_LEN: u64 = 512;
 
 pub struct Renderer {
     pub scale_factor: f64,
@@ -44,7 +47,26 @@ pub struct Renderer {
     screen_size_bind_group: wgpu::BindGroup,
     screen_size_buffer: wgpu::Buffer,
     staging_belt: StagingBelt,
-    glyph_brush: GlyphBrush<()>
+    glyph_brush: GlyphBrush<()>,
+    image_pipeline: ImagePipeline,
+    images: HashMap<ImageId, wgpu::BindGroup>,
+    icon_atlas: Option<IconAtlas>
+}
+
+/// Draws textured quads. Every image has its own bind group
+/// with the texture and the sampler in group 1.
+struct ImagePipeline {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct ImageVertex {
+    position: [i32; 2],
+    uv: [f32; 2],
+    tint: [u8; 4]
 }
 
 #[repr(C)]
@@ -59,14 +81,42 @@ struct Painter<'a> {
     clip: Option<Rect>,
     vertices: &'a mut Vec<Vertex>,
     indices: &'a mut Vec<u32>,
-    current_quad: u32
+    /// The first index of the mesh that isn't part of a draw call yet.
+    mesh_start: u32,
+    icon_atlas: Option<&'a IconAtlas>
 }
 
+/// The fonts and sizes by their microui id, shared with the text size
+/// handlers so that text is measured with the font it's drawn with.
+/// Unknown ids use the default font.
 #[derive(Clone, Debug)]
-pub struct FontMap(Rc<Vec<FontArc>>);
+pub struct FontMap(Rc<RefCell<FontSet>>);
+
+#[derive(Debug)]
+struct FontSet {
+    fonts: HashMap<FontId, FontFace>,
+    /// Tried in order for the characters that a font doesn't have.
+    fallbacks: Vec<(FontArc, GlyphBrushFontId)>
+}
+
+#[derive(Clone, Debug)]
+struct FontFace {
+    font: FontArc,
+    /// The id of the font in the glyph brush.
+    brush_id: GlyphBrushFontId,
+    size: f32
+}
+
+/// Characters that are next to each other when drawn and taken from the same font.
+struct TextRun {
+    font: FontArc,
+    brush_id: GlyphBrushFontId,
+    text: String
+}
 
 enum MicrouiDrawCall {
-    Mesh,
+    /// The indices of the part of the mesh drawn between texts and images.
+    Mesh(Range<u32>),
     Text {
         font: FontId,
         pos: Vec2,
@@ -74,10 +124,11 @@ enum MicrouiDrawCall {
         text: String,
         clip: Option<Rect>
     },
-    Icon {
-        text: String,
+    Image {
+        id: ImageId,
         rect: Rect,
-        color: Color,
+        uv: UvRect,
+        tint: Color,
         clip: Option<Rect>
     }
 }
@@ -86,14 +137,14 @@ impl MicrouiRenderer for Renderer {
     type TextSizeHandler = FontMap;
 
     fn init(
-        _window_builder: WindowBuilder,
-        _event_loop: &EventLoop<()>
+        window_builder: WindowBuilder,
+        event_loop: &EventLoop<()>
     ) -> Self {
-        /*
         env_logger::init();
 
-        let window = window_builder.build(&event_loop).unwrap();
+        let window = window_builder.build(event_loop).unwrap();
         let size = window.inner_size();
+        let scale_factor = window.scale_factor();
         
         let instance = wgpu::Instance::new(wgpu::Backends::all());
         let surface = unsafe { instance.create_surface(&window) };
@@ -213,7 +264,7 @@ impl MicrouiRenderer for Renderer {
                     entry_point: "fs_main",
                     targets: &[Some(wgpu::ColorTargetState {
                         format: config.format,
-                        blend: Some(wgpu::BlendState::REPLACE),
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
                         write_mask: wgpu::ColorWrites::ALL
                     })]
                 }),
@@ -239,6 +290,8 @@ impl MicrouiRenderer for Renderer {
         let glyph_brush = GlyphBrushBuilder::using_font(font_arc.clone())
             .build(&device, render_format);
 
+        let image_pipeline = ImagePipeline::new(&device, &bind_group_layout, config.format);
+
         let vertex_buffer = new_vertex_buffer(&device, mem::size_of::<Vertex>() as u64 * VERTEX_BUFFER_INITIAL_LEN);
         let index_buffer = new_index_buffer(&device, mem::size_of::<u32>() as u64 * INDEX_BUFFER_INITIAL_LEN);
 
@@ -248,7 +301,7 @@ impl MicrouiRenderer for Renderer {
             device,
             queue,
             config,
-            scale_factor: window.scale_factor(),
+            scale_factor,
             pipeline,
             vertices: Vec::with_capacity(VERTEX_BUFFER_INITIAL_LEN as usize),
             indices: Vec::with_capacity(INDEX_BUFFER_INITIAL_LEN as usize),
@@ -258,14 +311,14 @@ impl MicrouiRenderer for Renderer {
             screen_size_bind_group,
             staging_belt: StagingBelt::new(1024),
             glyph_brush,
-            font_map: FontMap::new(font_arc)
+            image_pipeline,
+            images: HashMap::new(),
+            icon_atlas: None,
+            font_map: FontMap::new(font_arc, GlyphBrushFontId(0))
         };
         instance.write_screen_size_buffer(size);
 
         instance
-        */
-
-        unimplemented!()
     }
 
     fn resize(&mut self, size: PhysicalSize<u32>, scale_factor: f64) {
@@ -292,7 +345,8 @@ impl MicrouiRenderer for Renderer {
 
         let mut painter = Painter::new(
             &mut self.vertices,
-            &mut self.indices
+            &mut self.indices,
+            self.icon_atlas.as_ref()
         );
 
         ctx.handle_commands(&mut painter);
@@ -301,23 +355,31 @@ impl MicrouiRenderer for Renderer {
         let size = self.size();
         let mut queued_text = false;
 
-        for call in calls {
-            match call {
-                MicrouiDrawCall::Mesh => {
-                    let index_bytes: &[u8] = bytemuck::cast_slice(&self.indices);
-                    let vertex_bytes: &[u8] = bytemuck::cast_slice(&self.vertices);
+        if !self.indices.is_empty() {
+            let index_bytes: &[u8] = bytemuck::cast_slice(&self.indices);
+            let vertex_bytes: &[u8] = bytemuck::cast_slice(&self.vertices);
 
-                    if index_bytes.len() as u64 > self.index_buffer.size() {
-                        self.index_buffer = new_index_buffer(&self.device, index_bytes.len() as u64)
-                    }
+            if index_bytes.len() as u64 > self.index_buffer.size() {
+                self.index_buffer = new_index_buffer(&self.device, index_bytes.len() as u64)
+            }
+
+            if vertex_bytes.len() as u64 > self.vertex_buffer.size() {
+                self.vertex_buffer = new_vertex_buffer(&self.device, vertex_bytes.len() as u64)
+            }
 
-                    if vertex_bytes.len() as u64 > self.vertex_buffer.size() {
-                        self.vertex_buffer = new_vertex_buffer(&self.device, vertex_bytes.len() as u64)
+            self.queue.write_buffer(&self.index_buffer, 0, index_bytes);
+            self.queue.write_buffer(&self.vertex_buffer, 0, vertex_bytes);
+        }
+
+        for call in calls {
+            match call {
+                MicrouiDrawCall::Mesh(range) => {
+                    // Text that came first is below the mesh.
+                    if queued_text {
+                        self.draw_queued_text(&mut encoder, &view, size);
+                        queued_text = false;
                     }
 
-                    self.queue.write_buffer(&self.index_buffer, 0, index_bytes);
-                    self.queue.write_buffer(&self.vertex_buffer, 0, vertex_bytes);
-        
                     let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                         label: Some("microui_render pass"),
                         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -337,35 +399,28 @@ impl MicrouiRenderer for Renderer {
                     render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
                     render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
 
-                    render_pass.draw_indexed(
-                        0..self.indices.len() as u32,
-                        0,
-                        0..1
-                    )
+                    render_pass.draw_indexed(range, 0, 0..1)
                 },
                 MicrouiDrawCall::Text { font, pos, color, text, clip } => {
                     if clip.is_some() && queued_text {
-                        self.glyph_brush.draw_queued(
-                            &self.device,
-                            &mut self.staging_belt,
-                            &mut encoder,
-                            &view,
-                            size.width,
-                            size.height
-                        ).unwrap();
-
+                        self.draw_queued_text(&mut encoder, &view, size);
                         queued_text = false;
                     } else {
                         queued_text = true;
                     }
 
+                    let (font_size, runs) = self.font_map.runs(font, &text);
+                    let font_size = font_size * self.scale_factor as f32;
+
                     self.glyph_brush.queue(Section {
                         screen_position: (pos.x as f32, pos.y as f32),
-                        text: vec![Text::new(&text)
-                            .with_scale(FONT_SIZE_PT)
-                            .with_color([color.r as f32, color.g as f32, color.b as f32, color.a as f32])
-                            .with_font_id(GlyphBrushFontId(font.0 as usize))
-                        ],
+                        text: runs.iter()
+                            .map(|run| Text::new(&run.text)
+                                .with_scale(font_size)
+                                .with_color([color.r as f32, color.g as f32, color.b as f32, color.a as f32])
+                                .with_font_id(run.brush_id)
+                            )
+                            .collect(),
                         ..Section::default()
                     });
 
@@ -380,56 +435,62 @@ impl MicrouiRenderer for Renderer {
                         ).unwrap();
                     }
                 },
-                MicrouiDrawCall::Icon { text, rect, color, clip } => {
-                    if clip.is_some() && queued_text {
-                        self.glyph_brush.draw_queued(
-                            &self.device,
-                            &mut self.staging_belt,
-                            &mut encoder,
-                            &view,
-                            size.width,
-                            size.height
-                        ).unwrap();
-
+                MicrouiDrawCall::Image { id, rect, uv, tint, clip } => {
+                    if queued_text {
+                        self.draw_queued_text(&mut encoder, &view, size);
                         queued_text = false;
-                    } else {
-                        queued_text = true;
                     }
 
-                    self.glyph_brush.queue(Section {
-                        bounds: (rect.w as f32, rect.h as f32),
-                        screen_position: (rect.x as f32, rect.y as f32),
-                        text: vec![Text::new(&text)
-                            .with_scale(FONT_SIZE_PT)
-                            .with_color([color.r as f32, color.g as f32, color.b as f32, color.a as f32])
-                            .with_font_id(GlyphBrushFontId(0))
-                        ],
-                        ..Section::default()
-                    });
+                    let Some(bind_group) = self.images.get(&id) else {
+                        continue;
+                    };
 
-                    if let Some(clip) = clip {
-                        self.glyph_brush.draw_queued_with_transform_and_scissoring(
-                            &self.device,
-                            &mut self.staging_belt,
-                            &mut encoder,
-                            &view,
-                            orthographic_projection(size.width, size.height),
-                            Region { x: clip.x as u32, y: clip.y as u32, width: clip.w as u32, height: clip.h as u32 }
-                        ).unwrap();
+                    let screen = Rect { x: 0, y: 0, w: size.width as i32, h: size.height as i32 };
+                    let clip = clip.unwrap_or(screen).intersect(screen);
+
+                    if clip.w <= 0 || clip.h <= 0 {
+                        continue;
                     }
+
+                    let tint = [tint.r, tint.g, tint.b, tint.a];
+                    let vertices = [
+                        ImageVertex { position: [rect.x, rect.y], uv: [uv.x, uv.y], tint },
+                        ImageVertex { position: [rect.x + rect.w, rect.y], uv: [uv.x + uv.w, uv.y], tint },
+                        ImageVertex { position: [rect.x, rect.y + rect.h], uv: [uv.x, uv.y + uv.h], tint },
+                        ImageVertex { position: [rect.x + rect.w, rect.y + rect.h], uv: [uv.x + uv.w, uv.y + uv.h], tint }
+                    ];
+
+                    let vertex_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                        label: Some("microui_image_vertex_buffer"),
+                        contents: bytemuck::cast_slice(&vertices),
+                        usage: wgpu::BufferUsages::VERTEX
+                    });
+
+                    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: Some("microui_image render pass"),
+                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                            view: &view,
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Load,
+                                store: true,
+                            },
+                        })],
+                        depth_stencil_attachment: None,
+                    });
+
+                    render_pass.set_scissor_rect(clip.x as u32, clip.y as u32, clip.w as u32, clip.h as u32);
+                    render_pass.set_pipeline(&self.image_pipeline.pipeline);
+                    render_pass.set_bind_group(0, &self.screen_size_bind_group, &[]);
+                    render_pass.set_bind_group(1, bind_group, &[]);
+                    render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                    render_pass.draw(0..4, 0..1);
                 }
             }
         }
 
         if queued_text {
-            self.glyph_brush.draw_queued(
-                &self.device,
-                &mut self.staging_belt,
-                &mut encoder,
-                &view,
-                size.width,
-                size.height
-            ).unwrap();
+            self.draw_queued_text(&mut encoder, &view, size);
         }
 
         self.staging_belt.finish();
@@ -440,6 +501,88 @@ impl MicrouiRenderer for Renderer {
         self.staging_belt.recall();
     }
 
+    fn set_image(&mut self, id: ImageId, width: u32, height: u32, rgba: &[u8]) {
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1
+        };
+
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("microui_image"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST
+        });
+
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All
+            },
+            rgba,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(width * 4),
+                rows_per_image: NonZeroU32::new(height)
+            },
+            size
+        );
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("microui_image bindgroup"),
+            layout: &self.image_pipeline.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view)
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.image_pipeline.sampler)
+                }
+            ]
+        });
+
+        self.images.insert(id, bind_group);
+    }
+
+    #[inline]
+    fn set_icon_atlas(&mut self, atlas: Option<IconAtlas>) {
+        self.icon_atlas = atlas;
+    }
+
+    fn set_font(&mut self, id: FontId, data: &[u8], size: f32) -> Result<(), Box<dyn Error>> {
+        let font = FontArc::try_from_vec(data.into())?;
+        let brush_id = self.glyph_brush.add_font(font.clone());
+
+        self.font_map.0.borrow_mut().fonts.insert(id, FontFace { font, brush_id, size });
+
+        Ok(())
+    }
+
+    fn set_font_size(&mut self, id: FontId, font: FontId, size: f32) {
+        let face = FontFace { size, ..self.font_map.get(font) };
+
+        self.font_map.0.borrow_mut().fonts.insert(id, face);
+    }
+
+    fn add_fallback_font(&mut self, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        let font = FontArc::try_from_vec(data.into())?;
+        let brush_id = self.glyph_brush.add_font(font.clone());
+
+        self.font_map.0.borrow_mut().fallbacks.push((font, brush_id));
+
+        Ok(())
+    }
+
     #[inline]
     fn text_size_handler(&self) -> Self::TextSizeHandler {
         self.font_map.clone()
@@ -457,19 +600,128 @@ impl Renderer {
         PhysicalSize::new(self.config.width, self.config.height)
     }
 
-    fn write_screen_size_buffer(&self, size: PhysicalSize<u32>) {
-        let logical_size = size.to_logical::<f32>(self.scale_factor);
+    fn draw_queued_text(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        size: PhysicalSize<u32>
+    ) {
+        self.glyph_brush.draw_queued(
+            &self.device,
+            &mut self.staging_belt,
+            encoder,
+            view,
+            size.width,
+            size.height
+        ).unwrap();
+    }
 
+    /// The core lays out in physical pixels, so the shaders map
+    /// positions to the physical size of the surface.
+    fn write_screen_size_buffer(&self, size: PhysicalSize<u32>) {
         self.queue.write_buffer(
             &self.screen_size_buffer,
             0,
             bytemuck::cast_slice(
-                &[logical_size.width as f32, logical_size.height as f32]
+                &[size.width as f32, size.height as f32]
             )
         );
     }
 }
 
+impl ImagePipeline {
+    fn new(
+        device: &wgpu::Device,
+        screen_size_layout: &wgpu::BindGroupLayout,
+        format: wgpu::TextureFormat
+    ) -> Self {
+        let shader = device.create_shader_module(
+            wgpu::include_wgsl!("../shaders/image.wgsl")
+        );
+
+        let bind_group_layout = device.create_bind_group_layout(
+            &wgpu::BindGroupLayoutDescriptor {
+                label: Some("microui image layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false
+                        },
+                        count: None
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None
+                    }
+                ]
+            }
+        );
+
+        let layout = device.create_pipeline_layout(
+            &wgpu::PipelineLayoutDescriptor {
+                label: Some("microui image pipeline layout"),
+                bind_group_layouts: &[screen_size_layout, &bind_group_layout],
+                push_constant_ranges: &[]
+            }
+        );
+
+        let pipeline = device.create_render_pipeline(
+            &wgpu::RenderPipelineDescriptor {
+                label: Some("microui image pipeline"),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: mem::size_of::<ImageVertex>() as u64,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &wgpu::vertex_attr_array![
+                            0 => Sint32x2,
+                            1 => Float32x2,
+                            2 => Unorm8x4
+                        ]
+                    }]
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL
+                    })]
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None
+            }
+        );
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("microui image sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler
+        }
+    }
+}
+
 fn new_index_buffer(device: &wgpu::Device, size: u64) -> wgpu::Buffer {
     device.create_buffer(&wgpu::BufferDescriptor {
         label: Some("microui_index_buffer"),
@@ -491,31 +743,74 @@ fn new_vertex_buffer(device: &wgpu::Device, size: u64) -> wgpu::Buffer {
 impl<'a> Painter<'a> {
     fn new(
         vertices: &'a mut Vec<Vertex>,
-        indices: &'a mut Vec<u32>
+        indices: &'a mut Vec<u32>,
+        icon_atlas: Option<&'a IconAtlas>
     ) -> Self {
         vertices.clear();
         indices.clear();
 
         Self {
-            draw_calls: vec![
-                // Vertices should be drawn before text.
-                MicrouiDrawCall::Mesh
-            ],
+            draw_calls: Vec::new(),
             clip: None,
             vertices,
             indices,
-            current_quad: 0
+            mesh_start: 0,
+            icon_atlas
         }
     }
 
     #[inline]
     fn finish(mut self) -> Vec<MicrouiDrawCall> {
-        if self.vertices.is_empty() {
-            self.draw_calls.swap_remove(0);
-        }
+        self.end_mesh();
 
         self.draw_calls
     }
+
+    /// Adds the part of the mesh since the last call as a draw call,
+    /// so that it's drawn below the texts and images that follow.
+    fn end_mesh(&mut self) {
+        let end = self.indices.len() as u32;
+
+        if end > self.mesh_start {
+            self.draw_calls.push(MicrouiDrawCall::Mesh(self.mesh_start..end));
+            self.mesh_start = end;
+        }
+    }
+
+    /// Adds a convex polygon to the mesh as a triangle fan. It's cut
+    /// to `clip` here since the whole mesh is drawn at once.
+    fn fill_convex(&mut self, points: &[[f32; 2]], color: Color, clip: Option<Rect>) {
+        let mut points = match clip {
+            Some(clip) => clip_polygon(points.to_vec(), clip),
+            None => points.to_vec()
+        };
+
+        if points.len() < 3 {
+            return;
+        }
+
+        // Only triangles that are clockwise on the screen pass the culling.
+        let area: f32 = points.iter()
+            .zip(points.iter().cycle().skip(1))
+            .map(|(p, q)| p[0] * q[1] - q[0] * p[1])
+            .sum();
+
+        if area < 0. {
+            points.reverse();
+        }
+
+        let base = self.vertices.len() as u32;
+        let color = [color.r, color.g, color.b, color.a];
+
+        self.vertices.extend(points.iter().map(|p| Vertex {
+            position: [p[0].round() as i32, p[1].round() as i32],
+            color
+        }));
+
+        for i in 1..points.len() as u32 - 1 {
+            self.indices.extend(&[base, base + i, base + i + 1]);
+        }
+    }
 }
 
 impl<'a> CommandHandler for Painter<'a> {
@@ -529,7 +824,9 @@ impl<'a> CommandHandler for Painter<'a> {
     #[inline]
     fn rect_cmd(&mut self, rect: Rect, color: Color) {
         assert!(self.clip.is_none());
-        
+
+        let base = self.vertices.len() as u32;
+
         self.vertices.extend(&[
             Vertex {
                 position: [rect.x, rect.y],
@@ -550,15 +847,13 @@ impl<'a> CommandHandler for Painter<'a> {
         ]);
 
         self.indices.extend(&[
-            self.current_quad * 4 + 0,
-            self.current_quad * 4 + 1,
-            self.current_quad * 4 + 2,
-            self.current_quad * 4 + 0,
-            self.current_quad * 4 + 2,
-            self.current_quad * 4 + 3,
+            base,
+            base + 1,
+            base + 2,
+            base,
+            base + 2,
+            base + 3,
         ]);
-
-        self.current_quad += 1;
     }
 
     #[inline]
@@ -567,9 +862,10 @@ impl<'a> CommandHandler for Painter<'a> {
         font: FontId,
         pos: Vec2,
         color: Color,
-        text: String
+        text: &str
     ) {
-        self.draw_calls.push(MicrouiDrawCall::Text { font, pos, color, text, clip: self.clip.take() });
+        self.end_mesh();
+        self.draw_calls.push(MicrouiDrawCall::Text { font, pos, color, text: text.into(), clip: self.clip.take() });
     }
 
     #[inline]
@@ -579,56 +875,266 @@ impl<'a> CommandHandler for Painter<'a> {
         rect: Rect,
         color: Color
     ) {
-        let text = match id {
-            Icon::Close => "",
-            Icon::Resize => "樂",
-            Icon::Check => "",
-            Icon::Collapsed => "",
-            Icon::Expanded => "",
-            Icon::None => return
-        }.into();
+        if let Some(atlas) = self.icon_atlas {
+            if let Some((dst, uv)) = atlas.locate(id, rect) {
+                self.image_cmd(atlas.image(), dst, uv, color);
+
+                return;
+            }
+        }
+
+        // Rects can't be scissored separately, so they're clipped here.
+        let clip = self.clip.take();
+
+        id.fallback(rect, |r| {
+            self.rect_cmd(clip.map_or(r, |x| r.intersect(x)), color);
+        });
+    }
+
+    #[inline]
+    fn image_cmd(
+        &mut self,
+        id: ImageId,
+        rect: Rect,
+        uv: UvRect,
+        tint: Color
+    ) {
+        self.end_mesh();
+        self.draw_calls.push(MicrouiDrawCall::Image { id, rect, uv, tint, clip: self.clip.take() });
+    }
+
+    fn line_cmd(
+        &mut self,
+        a: Vec2,
+        b: Vec2,
+        thickness: i32,
+        color: Color
+    ) {
+        let clip = self.clip.take();
+        let h = thickness.max(1) as f32 / 2.;
+
+        let (ax, ay, bx, by) = (a.x as f32, a.y as f32, b.x as f32, b.y as f32);
+        let len = ((bx - ax).powi(2) + (by - ay).powi(2)).sqrt().max(f32::EPSILON);
+        let (dx, dy) = ((bx - ax) / len * h, (by - ay) / len * h);
+
+        // Square ends stick out by half of the thickness.
+        self.fill_convex(&[
+            [ax - dx - dy, ay - dy + dx],
+            [bx + dx - dy, by + dy + dx],
+            [bx + dx + dy, by + dy - dx],
+            [ax - dx + dy, ay - dy - dx]
+        ], color, clip);
+    }
+
+    fn rounded_rect_cmd(
+        &mut self,
+        rect: Rect,
+        radius: i32,
+        color: Color
+    ) {
+        let clip = self.clip.take();
+        let r = radius.clamp(0, rect.w.min(rect.h) / 2) as f32;
+        let (x0, y0) = (rect.x as f32 + r, rect.y as f32 + r);
+        let (x1, y1) = ((rect.x + rect.w) as f32 - r, (rect.y + rect.h) as f32 - r);
+
+        let n = arc_segments(r, PI / 2.);
+        let mut points = Vec::with_capacity((n as usize + 1) * 4);
+
+        for (i, (cx, cy)) in [(x1, y0), (x1, y1), (x0, y1), (x0, y0)].into_iter().enumerate() {
+            let start = -PI / 2. + i as f32 * PI / 2.;
+
+            for j in 0..=n {
+                let a = start + j as f32 / n as f32 * PI / 2.;
+                points.push([cx + a.cos() * r, cy + a.sin() * r]);
+            }
+        }
+
+        self.fill_convex(&points, color, clip);
+    }
+
+    fn circle_cmd(
+        &mut self,
+        center: Vec2,
+        radius: i32,
+        color: Color
+    ) {
+        let clip = self.clip.take();
+        let r = radius as f32;
+        let n = arc_segments(r, TAU);
+
+        let points: Vec<_> = (0..n)
+            .map(|i| {
+                let a = i as f32 / n as f32 * TAU;
+                [center.x as f32 + a.cos() * r, center.y as f32 + a.sin() * r]
+            })
+            .collect();
+
+        self.fill_convex(&points, color, clip);
+    }
+
+    fn arc_cmd(
+        &mut self,
+        center: Vec2,
+        radius: i32,
+        start: Angle,
+        end: Angle,
+        thickness: i32,
+        color: Color
+    ) {
+        let clip = self.clip.take();
+        let h = thickness.max(1) as f32 / 2.;
+        let (inner, outer) = ((radius as f32 - h).max(0.), radius as f32 + h);
+        let (cx, cy) = (center.x as f32, center.y as f32);
+
+        let sweep = (end.0 - start.0).clamp(-TAU, TAU);
+        let n = arc_segments(outer, sweep.abs());
+        let point = |a: f32, r: f32| [cx + a.cos() * r, cy + a.sin() * r];
+
+        // Every segment of the ring is a separate quad.
+        for i in 0..n {
+            let a0 = start.0 + i as f32 / n as f32 * sweep;
+            let a1 = start.0 + (i + 1) as f32 / n as f32 * sweep;
+
+            self.fill_convex(&[
+                point(a0, outer),
+                point(a1, outer),
+                point(a1, inner),
+                point(a0, inner)
+            ], color, clip);
+        }
+    }
+
+    fn triangle_cmd(
+        &mut self,
+        points: [Vec2; 3],
+        color: Color
+    ) {
+        self.polygon_cmd(&points, color);
+    }
+
+    fn polygon_cmd(
+        &mut self,
+        points: &[Vec2],
+        color: Color
+    ) {
+        let clip = self.clip.take();
+        let points: Vec<_> = points.iter().map(|p| [p.x as f32, p.y as f32]).collect();
+
+        self.fill_convex(&points, color, clip);
+    }
+}
+
+/// The number of segments an arc is split into so that they're about 4 pixels long.
+fn arc_segments(radius: f32, sweep: f32) -> u32 {
+    ((radius * sweep / 4.).ceil() as u32).clamp(4, 64)
+}
 
-        self.draw_calls.push(MicrouiDrawCall::Icon { text, rect, color, clip: self.clip.take() });
+/// Cuts a convex polygon to the rect with the Sutherland-Hodgman algorithm.
+fn clip_polygon(mut points: Vec<[f32; 2]>, clip: Rect) -> Vec<[f32; 2]> {
+    let (x0, y0) = (clip.x as f32, clip.y as f32);
+    let (x1, y1) = (x0 + clip.w as f32, y0 + clip.h as f32);
+
+    // The axis of every edge, where it is and if the inside is above it.
+    for (axis, bound, above) in [(0, x0, true), (0, x1, false), (1, y0, true), (1, y1, false)] {
+        let inside = |p: &[f32; 2]| if above { p[axis] >= bound } else { p[axis] <= bound };
+        let cut = |p: &[f32; 2], q: &[f32; 2]| {
+            let t = (bound - p[axis]) / (q[axis] - p[axis]);
+
+            [p[0] + (q[0] - p[0]) * t, p[1] + (q[1] - p[1]) * t]
+        };
+
+        let input = mem::take(&mut points);
+
+        for (i, p) in input.iter().enumerate() {
+            let q = &input[(i + 1) % input.len()];
+
+            match (inside(p), inside(q)) {
+                (true, true) => points.push(*q),
+                (true, false) => points.push(cut(p, q)),
+                (false, true) => points.extend([cut(p, q), *q]),
+                (false, false) => {}
+            }
+        }
     }
+
+    points
 }
 
-/*
 impl FontMap {
     #[inline]
-    fn new(initial: FontArc) -> Self {
-        Self(Rc::new(vec![initial]))
+    fn new(font: FontArc, brush_id: GlyphBrushFontId) -> Self {
+        let face = FontFace { font, brush_id, size: FONT_SIZE_PT };
+
+        Self(Rc::new(RefCell::new(FontSet {
+            fonts: HashMap::from([(FontId::default(), face)]),
+            fallbacks: Vec::new()
+        })))
+    }
+
+    fn get(&self, id: FontId) -> FontFace {
+        let set = self.0.borrow();
+
+        set.fonts.get(&id)
+            .or_else(|| set.fonts.get(&FontId::default()))
+            .cloned()
+            .expect("The default font was removed.")
+    }
+
+    /// Splits `text` in the order it's drawn into runs of characters from the
+    /// same font. Every character is taken from the first of the font and the
+    /// fallback fonts that has it. Returns the size of the font with the runs.
+    fn runs(&self, id: FontId, text: &str) -> (f32, Vec<TextRun>) {
+        let face = self.get(id);
+        let set = self.0.borrow();
+
+        let mut runs: Vec<TextRun> = Vec::new();
+
+        for c in text::visual_order(text).chars() {
+            let (font, brush_id) = set.fallbacks.iter()
+                .find(|(font, _)| face.font.glyph_id(c).0 == 0 && font.glyph_id(c).0 != 0)
+                .map_or((&face.font, face.brush_id), |(font, id)| (font, *id));
+
+            match runs.last_mut() {
+                Some(run) if run.brush_id == brush_id => run.text.push(c),
+                _ => runs.push(TextRun { font: font.clone(), brush_id, text: c.into() })
+            }
+        }
+
+        (face.size, runs)
     }
 }
-*/
 
 impl TextSizeHandler for FontMap {
     fn text_width(&self, id: FontId, text: &str) -> i32 {
-        let font = &self.0[id.0 as usize].as_scaled(FONT_SIZE_PT);
-        
+        let (size, runs) = self.runs(id, text);
         let mut width = 0.;
-        let mut last_glyph_id = None;
 
-        for c in text.chars() {
-            let id = font.glyph_id(c);
+        for run in runs {
+            let font = run.font.as_scaled(size);
+            let mut last_glyph_id = None;
 
-            if let Some(last_id) = last_glyph_id {
-                // This is probably irrelevant most of the time
-                // since we are converting to i32...
-                width += font.kern(last_id, id);
-            }
+            for c in run.text.chars() {
+                let id = font.glyph_id(c);
+
+                if let Some(last_id) = last_glyph_id {
+                    // This is probably irrelevant most of the time
+                    // since we are converting to i32...
+                    width += font.kern(last_id, id);
+                }
 
-            last_glyph_id = Some(id);
-            width += font.h_advance(id);
+                last_glyph_id = Some(id);
+                width += font.h_advance(id);
+            }
         }
-        
+
         width as i32
     }
 
     #[inline]
     fn text_height(&self, id: FontId) -> i32 {
-        let font = &self.0[id.0 as usize].as_scaled(FONT_SIZE_PT);
+        let face = self.get(id);
 
-        font.height() as i32
+        face.font.as_scaled(face.size).height() as i32
     }
 }
 
@@ -639,3 +1145,11 @@ unsafe impl Zeroable for Vertex {
 }
 
 unsafe impl Pod for Vertex { }
+
+unsafe impl Zeroable for ImageVertex {
+    fn zeroed() -> Self {
+        unsafe { core::mem::zeroed() }
+    }
+}
+
+unsafe impl Pod for ImageVertex { }
@@ -0,0 +1,121 @@
+use std::{
+    ops::{Deref, DerefMut},
+    fmt, mem
+};
+
+use crate::Stack;
+
+/// A heap allocated vector with an optional maximum length.
+///
+/// Vectors with a limit allocate all of their memory up front and never
+/// reallocate. Pushing past the limit panics with the name of the
+/// [`crate::ContextConfig`] setting that controls it, unless the vector is
+/// lenient. Lenient vectors drop those items instead and keep count of them
+/// so that pushes and pops stay balanced.
+pub(crate) struct BoundedVec<T> {
+    items: Vec<T>,
+    limit: Option<usize>,
+    stack: Stack,
+    lenient: bool,
+    dropped: usize,
+    overflowed: bool
+}
+
+impl<T> BoundedVec<T> {
+    pub fn new(limit: Option<usize>, stack: Stack, lenient: bool) -> Self {
+        Self {
+            items: limit.map_or_else(Vec::new, Vec::with_capacity),
+            limit,
+            stack,
+            lenient,
+            dropped: 0,
+            overflowed: false
+        }
+    }
+
+    #[inline]
+    pub fn push(&mut self, item: T) {
+        if self.free_space() == 0 {
+            assert!(
+                self.lenient,
+                "Ran out of space for more than {} items. Raise the limit with ContextConfig::{}().",
+                self.items.len(),
+                self.stack.setting()
+            );
+
+            self.dropped += 1;
+            self.overflowed = true;
+
+            return;
+        }
+
+        self.items.push(item);
+    }
+
+    /// Pushes the item even if there's no space left in a lenient vector.
+    #[inline]
+    pub fn push_required(&mut self, item: T) {
+        if self.lenient {
+            self.items.push(item);
+        } else {
+            self.push(item);
+        }
+    }
+
+    /// Returns `None` for items that were dropped by [`BoundedVec::push`].
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.dropped > 0 {
+            self.dropped -= 1;
+
+            return None;
+        }
+
+        self.items.pop()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.dropped = 0;
+    }
+
+    /// The number of items that can be pushed before running out of space.
+    #[inline]
+    pub fn free_space(&self) -> usize {
+        self.limit.map_or(usize::MAX, |x| x.saturating_sub(self.items.len()))
+    }
+
+    #[inline]
+    pub fn is_bounded(&self) -> bool {
+        self.limit.is_some()
+    }
+
+    /// Returns the stack if items were dropped since the last call.
+    #[inline]
+    pub fn take_overflow(&mut self) -> Option<Stack> {
+        mem::take(&mut self.overflowed).then_some(self.stack)
+    }
+}
+
+impl<T> Deref for BoundedVec<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.items
+    }
+}
+
+impl<T> DerefMut for BoundedVec<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.items
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for BoundedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.items.iter()).finish()
+    }
+}
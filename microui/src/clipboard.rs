@@ -0,0 +1,26 @@
+/// Access to the clipboard used by text widgets for copy, cut and paste.
+pub trait Clipboard {
+    /// Returns `None` if the clipboard is empty or doesn't contain text.
+    fn get(&mut self) -> Option<String>;
+    fn set(&mut self, text: &str);
+}
+
+/// A clipboard that only lives as long as the [`Context`](crate::Context) it's
+/// been given to. This is the default until [`Context::set_clipboard`](crate::Context::set_clipboard)
+/// is called.
+#[derive(Clone, Default, Debug)]
+pub struct MemoryClipboard {
+    text: Option<String>
+}
+
+impl Clipboard for MemoryClipboard {
+    #[inline]
+    fn get(&mut self) -> Option<String> {
+        self.text.clone()
+    }
+
+    #[inline]
+    fn set(&mut self, text: &str) {
+        self.text = Some(text.into());
+    }
+}
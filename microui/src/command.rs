@@ -0,0 +1,244 @@
+use crate::{Context, Command, CommandHandler, Rect, UvRect, Vec2, Angle, Color, FontId, Icon, ImageId};
+
+/// A command of the last frame as returned by [`Context::commands`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DrawCommand<'a> {
+    /// Everything that follows is clipped to the rect until the next clip command.
+    Clip(Rect),
+    Rect {
+        rect: Rect,
+        color: Color
+    },
+    Text {
+        font: FontId,
+        pos: Vec2,
+        color: Color,
+        text: &'a str
+    },
+    Icon {
+        id: Icon,
+        rect: Rect,
+        color: Color
+    },
+    Image {
+        id: ImageId,
+        rect: Rect,
+        uv: UvRect,
+        tint: Color
+    },
+    Line {
+        a: Vec2,
+        b: Vec2,
+        thickness: i32,
+        color: Color
+    },
+    RoundedRect {
+        rect: Rect,
+        radius: i32,
+        color: Color
+    },
+    Circle {
+        center: Vec2,
+        radius: i32,
+        color: Color
+    },
+    /// The outline of a circle from `start` to `end`, going clockwise.
+    Arc {
+        center: Vec2,
+        radius: i32,
+        start: Angle,
+        end: Angle,
+        thickness: i32,
+        color: Color
+    },
+    Triangle {
+        points: [Vec2; 3],
+        color: Color
+    },
+    /// A filled convex polygon.
+    Polygon {
+        points: &'a [Vec2],
+        color: Color
+    }
+}
+
+/// Iterates the commands of the last frame in the order they should be drawn.
+/// See [`Context::commands`].
+#[derive(Clone)]
+pub struct Commands<'a> {
+    ctx: &'a Context,
+    index: usize
+}
+
+impl Context {
+    /// The commands of the last frame from back to front, with the
+    /// windows and popups already sorted by their z-index. This can be
+    /// called any number of times until the next [`Context::begin`].
+    #[inline]
+    pub fn commands(&self) -> Commands<'_> {
+        Commands { ctx: self, index: 0 }
+    }
+
+    /// Resolves the text of the command. Returns `None` for jumps.
+    pub(crate) fn draw_command(&self, cmd: Command) -> Option<DrawCommand<'_>> {
+        let cmd = match cmd {
+            Command::Jump(_) => return None,
+            Command::Clip(rect) => DrawCommand::Clip(rect),
+            Command::Rect { rect, color } => DrawCommand::Rect { rect, color },
+            Command::Text { font, pos, color, text } => DrawCommand::Text {
+                font,
+                pos,
+                color,
+                text: &self.text_arena[text.start..text.end]
+            },
+            Command::Icon { id, rect, color } => DrawCommand::Icon { id, rect, color },
+            Command::Image { id, rect, uv, tint } => DrawCommand::Image { id, rect, uv, tint },
+            Command::Line { a, b, thickness, color } => DrawCommand::Line { a, b, thickness, color },
+            Command::RoundedRect { rect, radius, color } => DrawCommand::RoundedRect { rect, radius, color },
+            Command::Circle { center, radius, color } => DrawCommand::Circle { center, radius, color },
+            Command::Arc { center, radius, start, end, thickness, color } => DrawCommand::Arc {
+                center,
+                radius,
+                start,
+                end,
+                thickness,
+                color
+            },
+            Command::Triangle { points, color } => DrawCommand::Triangle { points, color },
+            Command::Polygon { points, color } => DrawCommand::Polygon {
+                points: &self.point_arena[points.start..points.end],
+                color
+            }
+        };
+
+        Some(cmd)
+    }
+
+    /// Feeds the commands of the last frame to `handler` in the order they should be drawn.
+    /// This can be done any number of times until the next [`Context::begin`].
+    pub fn handle_commands(&self, handler: &mut impl CommandHandler) {
+        for cmd in self.commands() {
+            cmd.handle(handler);
+        }
+    }
+}
+
+impl<'a> DrawCommand<'a> {
+    /// Calls the method of `handler` that corresponds to the command.
+    #[inline]
+    pub fn handle(self, handler: &mut impl CommandHandler) {
+        match self {
+            Self::Clip(rect) => handler.clip_cmd(rect),
+            Self::Rect { rect, color } => handler.rect_cmd(rect, color),
+            Self::Text { font, pos, color, text } => handler.text_cmd(font, pos, color, text),
+            Self::Icon { id, rect, color } => handler.icon_cmd(id, rect, color),
+            Self::Image { id, rect, uv, tint } => handler.image_cmd(id, rect, uv, tint),
+            Self::Line { a, b, thickness, color } => handler.line_cmd(a, b, thickness, color),
+            Self::RoundedRect { rect, radius, color } => handler.rounded_rect_cmd(rect, radius, color),
+            Self::Circle { center, radius, color } => handler.circle_cmd(center, radius, color),
+            Self::Arc { center, radius, start, end, thickness, color } => {
+                handler.arc_cmd(center, radius, start, end, thickness, color)
+            },
+            Self::Triangle { points, color } => handler.triangle_cmd(points, color),
+            Self::Polygon { points, color } => handler.polygon_cmd(points, color)
+        }
+    }
+}
+
+impl<'a> Iterator for Commands<'a> {
+    type Item = DrawCommand<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let cmd = *self.ctx.command_list.get(self.index)?;
+            self.index += 1;
+
+            if let Command::Jump(dst) = cmd {
+                self.index = dst;
+            } else {
+                return self.ctx.draw_command(cmd);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Window, MouseButton, rect, vec2};
+    use crate::test_util::{Handler, Counter};
+
+    #[test]
+    fn z_order() {
+        let mut ctx = Context::new(Handler);
+
+        let frame = |ctx: &mut Context| {
+            ctx.begin();
+
+            for (title, r) in [("A", rect(0, 0, 100, 100)), ("B", rect(50, 50, 100, 100))] {
+                Window::new(title, r).show(ctx, |ctx| ctx.label(title));
+            }
+
+            ctx.end();
+
+            ctx.commands()
+                .filter_map(|cmd| match cmd {
+                    DrawCommand::Text { text, .. } if text.len() == 1 => Some(text.to_owned()),
+                    _ => None
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The window created last is on top. Every window shows its title and a label.
+        assert_eq!(frame(&mut ctx), ["A", "A", "B", "B"]);
+
+        ctx.input_mouse_down(vec2(10, 10), MouseButton::Left);
+        frame(&mut ctx);
+        assert_eq!(frame(&mut ctx), ["B", "B", "A", "A"]);
+
+        let commands: Vec<_> = ctx.commands().collect();
+        assert_eq!(ctx.commands().collect::<Vec<_>>(), commands);
+    }
+
+    #[test]
+    fn handle_twice() {
+        let mut ctx = Context::new(Handler);
+
+        ctx.begin();
+        Window::new("Window", rect(0, 0, 100, 100)).show(&mut ctx, |ctx| ctx.label("Label"));
+        ctx.end();
+
+        let mut counter = Counter(0);
+        ctx.handle_commands(&mut counter);
+        assert!(counter.0 > 0);
+
+        // Commands can be handled more than once.
+        let first = counter.0;
+        ctx.handle_commands(&mut counter);
+        assert_eq!(counter.0, first * 2);
+    }
+
+    #[test]
+    fn shapes() {
+        let mut ctx = Context::new(Handler);
+        let points = [vec2(20, 20), vec2(60, 20), vec2(40, 50)];
+
+        ctx.begin();
+        Window::new("Shapes", rect(0, 0, 100, 100)).show(&mut ctx, |ctx| {
+            ctx.draw_polygon(&points, Color::rgb(255, 0, 0));
+            ctx.draw_circle(vec2(50, 50), 10, Color::rgb(0, 255, 0));
+            // Outside of the window.
+            ctx.draw_line(vec2(200, 200), vec2(300, 300), 2, Color::rgb(0, 0, 255));
+        });
+        ctx.end();
+
+        let shapes: Vec<_> = ctx.commands()
+            .filter(|cmd| matches!(cmd, DrawCommand::Polygon { .. } | DrawCommand::Circle { .. } | DrawCommand::Line { .. }))
+            .collect();
+
+        assert_eq!(shapes, [
+            DrawCommand::Polygon { points: &points, color: Color::rgb(255, 0, 0) },
+            DrawCommand::Circle { center: vec2(50, 50), radius: 10, color: Color::rgb(0, 255, 0) }
+        ]);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    COMMAND_LIST_SIZE, ROOT_LIST_SIZE, CONTAINER_STACK_SIZE, CLIP_STACK_SIZE, ID_STACK_SIZE,
+    LAYOUT_STACK_SIZE, CONTAINER_POOL_SIZE, TREENODE_POOL_SIZE, FOCUS_LIST_SIZE
+};
+
+/// The capacities of the lists and pools of a [`crate::Context`],
+/// passed to [`crate::Context::with_config`].
+///
+/// A limited capacity is allocated once when the context is created and
+/// running out of it panics. [`ContextConfig::growable`] removes the limits
+/// so that everything grows as needed instead.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ContextConfig {
+    pub(crate) command_list: Option<usize>,
+    pub(crate) root_list: Option<usize>,
+    pub(crate) container_stack: Option<usize>,
+    pub(crate) clip_stack: Option<usize>,
+    pub(crate) id_stack: Option<usize>,
+    pub(crate) layout_stack: Option<usize>,
+    pub(crate) container_pool: Option<usize>,
+    pub(crate) treenode_pool: Option<usize>,
+    pub(crate) focus_list: Option<usize>,
+    pub(crate) diagnostics: bool
+}
+
+impl ContextConfig {
+    /// No limits on any of the capacities. Containers and tree nodes are
+    /// then only replaced after they weren't used for [`crate::POOL_EVICT_FRAMES`].
+    pub const fn growable() -> Self {
+        Self {
+            command_list: None,
+            root_list: None,
+            container_stack: None,
+            clip_stack: None,
+            id_stack: None,
+            layout_stack: None,
+            container_pool: None,
+            treenode_pool: None,
+            focus_list: None,
+            diagnostics: false
+        }
+    }
+
+    /// The maximum number of draw commands per frame.
+    #[inline]
+    pub fn command_list_size(mut self, size: usize) -> Self {
+        self.command_list = Some(size);
+
+        self
+    }
+
+    /// The maximum number of windows and popups shown per frame.
+    #[inline]
+    pub fn root_list_size(mut self, size: usize) -> Self {
+        self.root_list = Some(size);
+
+        self
+    }
+
+    /// The maximum nesting depth of containers.
+    #[inline]
+    pub fn container_stack_size(mut self, size: usize) -> Self {
+        self.container_stack = Some(size);
+
+        self
+    }
+
+    #[inline]
+    pub fn clip_stack_size(mut self, size: usize) -> Self {
+        self.clip_stack = Some(size);
+
+        self
+    }
+
+    #[inline]
+    pub fn id_stack_size(mut self, size: usize) -> Self {
+        self.id_stack = Some(size);
+
+        self
+    }
+
+    #[inline]
+    pub fn layout_stack_size(mut self, size: usize) -> Self {
+        self.layout_stack = Some(size);
+
+        self
+    }
+
+    /// The number of containers whose state is retained. The least recently
+    /// used one is replaced when a new container doesn't fit.
+    #[inline]
+    pub fn container_pool_size(mut self, size: usize) -> Self {
+        self.container_pool = Some(size);
+
+        self
+    }
+
+    /// The number of expanded tree nodes and headers that are remembered.
+    #[inline]
+    pub fn treenode_pool_size(mut self, size: usize) -> Self {
+        self.treenode_pool = Some(size);
+
+        self
+    }
+
+    /// The maximum number of widgets that Tab moves the focus between.
+    /// Widgets past it can't be focused with the keyboard.
+    #[inline]
+    pub fn focus_list_size(mut self, size: usize) -> Self {
+        self.focus_list = Some(size);
+
+        self
+    }
+
+    /// Collects running out of space and misuse of the API as [`crate::UiError`]s
+    /// instead of panicking. The offending items are dropped and unbalanced
+    /// containers are ended so that the frame can still be drawn.
+    #[inline]
+    pub fn diagnostics(mut self, enabled: bool) -> Self {
+        self.diagnostics = enabled;
+
+        self
+    }
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            command_list: Some(COMMAND_LIST_SIZE),
+            root_list: Some(ROOT_LIST_SIZE),
+            container_stack: Some(CONTAINER_STACK_SIZE),
+            clip_stack: Some(CLIP_STACK_SIZE),
+            id_stack: Some(ID_STACK_SIZE),
+            layout_stack: Some(LAYOUT_STACK_SIZE),
+            container_pool: Some(CONTAINER_POOL_SIZE),
+            treenode_pool: Some(TREENODE_POOL_SIZE),
+            focus_list: Some(FOCUS_LIST_SIZE),
+            diagnostics: false
+        }
+    }
+}
@@ -159,6 +159,11 @@ impl<T, const N: usize> ConstVec<T, N> {
         self.items[index].as_mut_ptr()
     }
 
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.items.as_mut_ptr() as *mut T
+    }
+
     #[inline]
     pub unsafe fn read_at(&self, index: usize) -> T {
         self.items[index].assume_init_read()
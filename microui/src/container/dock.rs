@@ -0,0 +1,549 @@
+use std::mem;
+
+use crate::{Rect, Vec2, rect};
+
+/// The direction a [`DockNode::Split`] divides its area in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DockAxis {
+    /// The children are placed side by side.
+    Horizontal,
+    /// The children are placed on top of each other.
+    Vertical
+}
+
+/// A node of the dock tree. Windows are referred to by their titles.
+#[derive(Clone, PartialEq, Debug)]
+pub enum DockNode {
+    /// Divides the area between two nodes. `ratio` is the share
+    /// of the area that goes to `first`.
+    Split {
+        axis: DockAxis,
+        ratio: f32,
+        first: Box<DockNode>,
+        second: Box<DockNode>
+    },
+    /// Windows sharing the same area where only the `active` one is shown.
+    Tabs {
+        windows: Vec<String>,
+        active: usize
+    }
+}
+
+/// Where a window is docked relative to a node.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum DockPlace {
+    Tab,
+    Left,
+    Right,
+    Top,
+    Bottom
+}
+
+/// The tree a window is docked in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum DockGroup {
+    /// The tree that spans the dock area.
+    Area,
+    /// The [`FloatingDock`] at this index.
+    Floating(usize),
+    /// A new floating group started by the floating window of the container
+    /// at this index. Only used as a target.
+    Window(usize)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct DockTarget {
+    pub group: DockGroup,
+    /// The depth-first index of the node or `None` for the whole area of the group.
+    pub node: Option<usize>,
+    pub place: DockPlace
+}
+
+/// Windows docked onto a floating window. They share the area
+/// of that window, which doesn't move with them.
+#[derive(Clone, Debug)]
+pub(crate) struct FloatingDock {
+    pub root: DockNode,
+    pub area: Rect
+}
+
+/// The tab group a docked window is in.
+#[derive(Clone, Debug)]
+pub(crate) struct DockLeaf {
+    pub group: DockGroup,
+    pub index: usize,
+    pub rect: Rect,
+    pub windows: Vec<String>,
+    pub active: usize
+}
+
+/// A window that is being dragged by its title bar.
+#[derive(Clone, Debug)]
+pub(crate) struct DockDrag {
+    pub window: String,
+    pub container: usize,
+    pub target: Option<(DockTarget, Rect)>
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct Dock {
+    pub root: Option<DockNode>,
+    pub area: Rect,
+    pub floating: Vec<FloatingDock>,
+    pub drag: Option<DockDrag>,
+    pub resizing: Option<(DockGroup, usize)>
+}
+
+impl DockNode {
+    #[inline]
+    pub fn tabs<T: Into<String>>(windows: impl IntoIterator<Item = T>) -> Self {
+        Self::Tabs {
+            windows: windows.into_iter().map(Into::into).collect(),
+            active: 0
+        }
+    }
+
+    #[inline]
+    pub fn split(axis: DockAxis, ratio: f32, first: DockNode, second: DockNode) -> Self {
+        Self::Split {
+            axis,
+            ratio,
+            first: Box::new(first),
+            second: Box::new(second)
+        }
+    }
+
+    /// Returns `true` if the window with the given title is docked somewhere in this node.
+    pub fn contains(&self, window: &str) -> bool {
+        match self {
+            Self::Split { first, second, .. } => first.contains(window) || second.contains(window),
+            Self::Tabs { windows, .. } => windows.iter().any(|x| x == window)
+        }
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        matches!(self, Self::Tabs { windows, .. } if windows.is_empty())
+    }
+
+    /// Returns `true` if at most one window is left in this node.
+    #[inline]
+    fn is_single(&self) -> bool {
+        matches!(self, Self::Tabs { windows, .. } if windows.len() <= 1)
+    }
+
+    /// Calls `f` with the depth-first index, the node and its area for this node and all of its children.
+    fn visit(&self, r: Rect, gap: i32, index: &mut usize, f: &mut impl FnMut(usize, &DockNode, Rect)) {
+        f(*index, self, r);
+        *index += 1;
+
+        if let Self::Split { axis, ratio, first, second } = self {
+            let (a, b) = split_rect(r, *axis, *ratio, gap);
+
+            first.visit(a, gap, index, f);
+            second.visit(b, gap, index, f);
+        }
+    }
+
+    fn nth_mut<'a>(&'a mut self, index: &mut usize) -> Option<&'a mut DockNode> {
+        if *index == 0 {
+            return Some(self);
+        }
+
+        *index -= 1;
+
+        match self {
+            Self::Split { first, second, .. } => {
+                first.nth_mut(index).or_else(|| second.nth_mut(index))
+            },
+            Self::Tabs { .. } => None
+        }
+    }
+
+    /// Removes the window from this node and collapses the
+    /// splits left with an empty side. Returns `true` if it was found.
+    fn remove(&mut self, window: &str) -> bool {
+        let (found, remaining) = match self {
+            Self::Split { first, second, .. } => {
+                let found = first.remove(window) || second.remove(window);
+
+                let remaining = if first.is_empty() {
+                    Some(mem::replace(&mut **second, Self::tabs::<String>([])))
+                } else if second.is_empty() {
+                    Some(mem::replace(&mut **first, Self::tabs::<String>([])))
+                } else {
+                    None
+                };
+
+                (found, remaining)
+            },
+            Self::Tabs { windows, active } => {
+                match windows.iter().position(|x| x == window) {
+                    Some(index) => {
+                        windows.remove(index);
+
+                        if index < *active || *active >= windows.len() {
+                            *active = active.saturating_sub(1);
+                        }
+
+                        (true, None)
+                    },
+                    None => (false, None)
+                }
+            }
+        };
+
+        if let Some(node) = remaining {
+            *self = node;
+        }
+
+        found
+    }
+}
+
+impl Dock {
+    /// The trees of the dock area and of the floating groups along with their areas.
+    fn trees(&self) -> impl Iterator<Item = (DockGroup, &DockNode, Rect)> {
+        let floating = self.floating.iter()
+            .enumerate()
+            .map(|(i, x)| (DockGroup::Floating(i), &x.root, x.area));
+
+        self.root.iter()
+            .map(|x| (DockGroup::Area, x, self.area))
+            .chain(floating)
+    }
+
+    fn tree_mut(&mut self, group: DockGroup) -> Option<&mut DockNode> {
+        match group {
+            DockGroup::Area => self.root.as_mut(),
+            DockGroup::Floating(i) => self.floating.get_mut(i).map(|x| &mut x.root),
+            DockGroup::Window(_) => None
+        }
+    }
+
+    /// Finds the tab group that contains the window.
+    pub fn leaf(&self, window: &str, gap: i32) -> Option<DockLeaf> {
+        let mut leaf = None;
+
+        for (group, root, area) in self.trees() {
+            root.visit(area, gap, &mut 0, &mut |index, node, r| {
+                if let DockNode::Tabs { windows, active } = node {
+                    if windows.iter().any(|x| x == window) {
+                        leaf = Some(DockLeaf {
+                            group,
+                            index,
+                            rect: r,
+                            windows: windows.clone(),
+                            active: *active
+                        });
+                    }
+                }
+            });
+        }
+
+        leaf
+    }
+
+    /// Returns `true` if the window is docked in the dock area or in a floating group.
+    pub fn contains(&self, window: &str) -> bool {
+        self.trees().any(|(_, root, _)| root.contains(window))
+    }
+
+    /// Returns where a window dropped at `pos` in the dock area would be
+    /// docked along with the area it would take up. The edges of the dock
+    /// area and the tab groups docked in it are targets.
+    pub fn target_at(&self, pos: Vec2, gap: i32, title_height: i32) -> Option<(DockTarget, Rect)> {
+        let area = self.area;
+
+        if !area.overlaps(pos) {
+            return None;
+        }
+
+        let leaf = self.root.as_ref().and_then(|x| leaf_at(x, area, pos, gap));
+
+        // The edges of the dock area split the whole tree,
+        // except where they overlap the title bars of tab groups.
+        let edge = if leaf.is_some_and(|(_, r)| pos.y < r.y + title_height) {
+            None
+        } else if pos.x < area.x + title_height {
+            Some(DockPlace::Left)
+        } else if pos.x >= area.x + area.w - title_height {
+            Some(DockPlace::Right)
+        } else if pos.y < area.y + title_height {
+            Some(DockPlace::Top)
+        } else if pos.y >= area.y + area.h - title_height {
+            Some(DockPlace::Bottom)
+        } else {
+            None
+        };
+
+        if let Some(place) = edge {
+            let r = if self.root.is_some() {
+                place_rect(area, place, gap)
+            } else {
+                area
+            };
+
+            return Some((DockTarget { group: DockGroup::Area, node: None, place }, r));
+        }
+
+        leaf.map(|(index, r)| {
+            let place = place_at(r, pos, title_height);
+
+            (DockTarget { group: DockGroup::Area, node: Some(index), place }, place_rect(r, place, gap))
+        })
+    }
+
+    /// Like [`Dock::target_at`] for the tab groups of the floating group `group`.
+    pub fn target_in(&self, group: usize, pos: Vec2, gap: i32, title_height: i32) -> Option<(DockTarget, Rect)> {
+        let floating = self.floating.get(group)?;
+        let (index, r) = leaf_at(&floating.root, floating.area, pos, gap)?;
+        let place = place_at(r, pos, title_height);
+
+        Some((DockTarget { group: DockGroup::Floating(group), node: Some(index), place }, place_rect(r, place, gap)))
+    }
+
+    /// Returns where a window dropped at `pos` onto the floating window of the
+    /// container at `index`, whose rect is `r`, would be docked.
+    pub fn target_on_window(index: usize, r: Rect, pos: Vec2, gap: i32, title_height: i32) -> (DockTarget, Rect) {
+        let place = place_at(r, pos, title_height);
+
+        (DockTarget { group: DockGroup::Window(index), node: None, place }, place_rect(r, place, gap))
+    }
+
+    /// Starts a floating group with a window that takes up `area`.
+    /// Returns the group to dock other windows into.
+    pub fn float(&mut self, window: String, area: Rect) -> DockGroup {
+        self.floating.push(FloatingDock {
+            root: DockNode::tabs([window]),
+            area
+        });
+
+        DockGroup::Floating(self.floating.len() - 1)
+    }
+
+    pub fn insert(&mut self, window: String, target: DockTarget) {
+        if target.group == DockGroup::Area && self.root.is_none() {
+            self.root = Some(DockNode::tabs([window]));
+
+            return;
+        }
+
+        let node = match (target.node, self.tree_mut(target.group)) {
+            (_, None) => return,
+            (None, Some(root)) => root,
+            (Some(index), Some(root)) => match root.nth_mut(&mut { index }) {
+                Some(node) => node,
+                None => return
+            }
+        };
+
+        let (axis, before) = match target.place {
+            DockPlace::Tab => {
+                if let DockNode::Tabs { windows, active } = node {
+                    windows.push(window);
+                    *active = windows.len() - 1;
+                }
+
+                return;
+            },
+            DockPlace::Left => (DockAxis::Horizontal, true),
+            DockPlace::Right => (DockAxis::Horizontal, false),
+            DockPlace::Top => (DockAxis::Vertical, true),
+            DockPlace::Bottom => (DockAxis::Vertical, false)
+        };
+
+        let old = mem::replace(node, DockNode::tabs::<String>([]));
+        let new = DockNode::tabs([window]);
+
+        *node = if before {
+            DockNode::split(axis, 0.5, new, old)
+        } else {
+            DockNode::split(axis, 0.5, old, new)
+        };
+    }
+
+    pub fn remove(&mut self, window: &str) -> bool {
+        let mut found = self.root.as_mut().is_some_and(|x| x.remove(window));
+
+        if self.root.as_ref().is_some_and(DockNode::is_empty) {
+            self.root = None;
+        }
+
+        for floating in &mut self.floating {
+            found |= floating.root.remove(window);
+        }
+
+        // The window left alone in a floating group floats on its own again.
+        self.floating.retain(|x| !x.root.is_single());
+
+        found
+    }
+
+    pub fn set_active(&mut self, group: DockGroup, node: usize, tab: usize) {
+        if let Some(DockNode::Tabs { active, .. }) = self.tree_mut(group).and_then(|x| x.nth_mut(&mut { node })) {
+            *active = tab;
+        }
+    }
+
+    /// Returns the group and the index of the split whose divider is at `pos`.
+    pub fn splitter_at(&self, pos: Vec2, gap: i32) -> Option<(DockGroup, usize)> {
+        let mut splitter = None;
+
+        for (group, root, area) in self.trees() {
+            root.visit(area, gap, &mut 0, &mut |index, node, r| {
+                if let DockNode::Split { axis, ratio, .. } = node {
+                    let (a, _) = split_rect(r, *axis, *ratio, gap);
+
+                    let divider = match axis {
+                        DockAxis::Horizontal => rect(a.x + a.w, r.y, gap, r.h),
+                        DockAxis::Vertical => rect(r.x, a.y + a.h, r.w, gap)
+                    };
+
+                    if divider.overlaps(pos) {
+                        splitter = Some((group, index));
+                    }
+                }
+            });
+        }
+
+        splitter
+    }
+
+    /// Moves the divider of the split at `index` in `group` to `pos`.
+    pub fn resize(&mut self, group: DockGroup, index: usize, pos: Vec2, gap: i32) {
+        let mut area = None;
+
+        for (g, root, r) in self.trees() {
+            if g == group {
+                root.visit(r, gap, &mut 0, &mut |i, _, r| {
+                    if i == index {
+                        area = Some(r);
+                    }
+                });
+            }
+        }
+
+        let node = self.tree_mut(group).and_then(|x| x.nth_mut(&mut { index }));
+
+        if let (Some(r), Some(DockNode::Split { axis, ratio, .. })) = (area, node) {
+            let (offset, size) = match axis {
+                DockAxis::Horizontal => (pos.x - r.x, r.w - gap),
+                DockAxis::Vertical => (pos.y - r.y, r.h - gap)
+            };
+
+            if size > 0 {
+                *ratio = (offset as f32 / size as f32).clamp(0.1, 0.9);
+            }
+        }
+    }
+}
+
+/// Returns the depth-first index and the area of the tab group at `pos`.
+fn leaf_at(root: &DockNode, area: Rect, pos: Vec2, gap: i32) -> Option<(usize, Rect)> {
+    let mut leaf = None;
+
+    root.visit(area, gap, &mut 0, &mut |index, node, r| {
+        if matches!(node, DockNode::Tabs { .. }) && r.overlaps(pos) {
+            leaf = Some((index, r));
+        }
+    });
+
+    leaf
+}
+
+fn split_rect(r: Rect, axis: DockAxis, ratio: f32, gap: i32) -> (Rect, Rect) {
+    match axis {
+        DockAxis::Horizontal => {
+            let w = ((r.w - gap) as f32 * ratio).round() as i32;
+
+            (rect(r.x, r.y, w, r.h), rect(r.x + w + gap, r.y, r.w - w - gap, r.h))
+        },
+        DockAxis::Vertical => {
+            let h = ((r.h - gap) as f32 * ratio).round() as i32;
+
+            (rect(r.x, r.y, r.w, h), rect(r.x, r.y + h + gap, r.w, r.h - h - gap))
+        }
+    }
+}
+
+/// Dropping onto the title bar or the middle of a tab group adds a tab,
+/// otherwise the group is split at the closest edge.
+fn place_at(r: Rect, pos: Vec2, title_height: i32) -> DockPlace {
+    if pos.y < r.y + title_height {
+        return DockPlace::Tab;
+    }
+
+    let x = (pos.x - r.x) as f32 / r.w as f32;
+    let y = (pos.y - r.y) as f32 / r.h as f32;
+
+    let edges = [
+        (x, DockPlace::Left),
+        (1. - x, DockPlace::Right),
+        (y, DockPlace::Top),
+        (1. - y, DockPlace::Bottom)
+    ];
+
+    let (distance, place) = edges.into_iter()
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .unwrap();
+
+    if distance < 0.25 {
+        place
+    } else {
+        DockPlace::Tab
+    }
+}
+
+fn place_rect(r: Rect, place: DockPlace, gap: i32) -> Rect {
+    match place {
+        DockPlace::Tab => r,
+        DockPlace::Left => split_rect(r, DockAxis::Horizontal, 0.5, gap).0,
+        DockPlace::Right => split_rect(r, DockAxis::Horizontal, 0.5, gap).1,
+        DockPlace::Top => split_rect(r, DockAxis::Vertical, 0.5, gap).0,
+        DockPlace::Bottom => split_rect(r, DockAxis::Vertical, 0.5, gap).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec2;
+
+    #[test]
+    fn tree() {
+        let mut dock = Dock {
+            area: rect(0, 0, 204, 100),
+            ..Default::default()
+        };
+
+        let target = |node, place| DockTarget { group: DockGroup::Area, node, place };
+
+        dock.insert("A".into(), target(None, DockPlace::Left));
+        dock.insert("B".into(), target(Some(0), DockPlace::Right));
+        dock.insert("C".into(), target(Some(2), DockPlace::Tab));
+
+        assert_eq!(dock.root, Some(DockNode::split(
+            DockAxis::Horizontal,
+            0.5,
+            DockNode::tabs(["A"]),
+            DockNode::Tabs { windows: vec!["B".into(), "C".into()], active: 1 }
+        )));
+
+        let leaf = dock.leaf("B", 4).unwrap();
+        assert_eq!((leaf.index, leaf.rect, leaf.active), (2, rect(104, 0, 100, 100), 1));
+
+        assert_eq!(dock.target_at(vec2(50, 10), 4, 24).unwrap().0, target(Some(1), DockPlace::Tab));
+        assert_eq!(dock.target_at(vec2(190, 80), 4, 12).unwrap().0, target(Some(2), DockPlace::Right));
+        assert_eq!(dock.target_at(vec2(100, 98), 4, 12).unwrap().0, target(None, DockPlace::Bottom));
+
+        assert_eq!(dock.splitter_at(vec2(101, 50), 4), Some((DockGroup::Area, 0)));
+        dock.resize(DockGroup::Area, 0, vec2(50, 50), 4);
+        assert_eq!(dock.leaf("A", 4).unwrap().rect, rect(0, 0, 50, 100));
+
+        assert!(dock.remove("A"));
+        assert_eq!(dock.root, Some(DockNode::Tabs { windows: vec!["B".into(), "C".into()], active: 1 }));
+        assert!(dock.remove("C") && dock.remove("B"));
+        assert_eq!(dock.root, None);
+    }
+}
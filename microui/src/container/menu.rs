@@ -0,0 +1,381 @@
+use std::mem;
+
+use crate::{Context, ContainerOptions, Icon, MouseButton, WidgetInteraction, WidgetColor, Rect, rect};
+use crate::widget::{menu_row, draw_menu_row};
+use super::Popup;
+
+/// A popup menu that opens at the mouse when the last widget is right-clicked,
+/// filled with [`MenuItem`](crate::MenuItem)s, [`Context::menu_separator`]s
+/// and [`Submenu`]s. It closes when an item is clicked or when something
+/// outside of it and its submenus is clicked.
+pub struct ContextMenu {
+    popup: Popup,
+    on_body: bool
+}
+
+/// An entry of a menu that opens another menu next to it while it's hovered.
+pub struct Submenu {
+    text: String,
+    enabled: bool
+}
+
+impl ContextMenu {
+    #[inline]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            popup: Popup::new(name),
+            on_body: false
+        }
+    }
+
+    /// Opens when the body of the current container is right-clicked instead of
+    /// the last widget. Show it after the widgets in the container, so that the
+    /// menus of the widgets are opened instead when they are right-clicked.
+    #[inline]
+    pub fn on_body(mut self) -> Self {
+        self.on_body = true;
+
+        self
+    }
+
+    /// This must be called unconditionally, like [`Popup::show`].
+    pub fn show(self, ctx: &mut Context, contents: impl FnOnce(&mut Context)) {
+        let clicked = ctx.mouse_pressed(MouseButton::Right) && if self.on_body {
+            ctx.in_hover_root() && ctx.current_container().body.overlaps(ctx.mouse_pos)
+        } else {
+            ctx.last_id.is_some_and(|id| ctx.is_hovered(id))
+        };
+
+        if clicked {
+            ctx.consume_mouse(MouseButton::Right);
+            self.popup.open(ctx);
+        }
+
+        // Opened at the mouse, so it's flipped to the other side of it.
+        if let Some(cnt_idx) = self.popup.container_index(ctx) {
+            let r = ctx.containers[cnt_idx].rect;
+            keep_in_area(ctx, cnt_idx, r.x - r.w, r.y - r.h);
+        }
+
+        self.popup.show(ctx, |ctx| {
+            menu_body(ctx, contents);
+        });
+
+        ctx.close_menus = false;
+    }
+}
+
+impl Submenu {
+    #[inline]
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            enabled: true
+        }
+    }
+
+    /// Disabled submenus are grayed out and don't open.
+    #[inline]
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+
+        self
+    }
+
+    /// Must be called inside of a menu.
+    pub fn show(self, ctx: &mut Context, contents: impl FnOnce(&mut Context)) {
+        let id = ctx.create_id(&self.text);
+        let r = menu_row(ctx, &self.text);
+
+        let interaction = if self.enabled {
+            WidgetInteraction::default()
+        } else {
+            WidgetInteraction::default().no_interact()
+        };
+
+        ctx.update_widget(id, r, interaction);
+
+        let name = format!("!submenu {}", self.text);
+        let cnt_id = ctx.create_id(&name);
+        let parent = ctx.current_root_index();
+
+        if ctx.is_hovered(id) {
+            if let Some(cnt_idx) = ctx.get_container(cnt_id, ContainerOptions::default()) {
+                // New containers start out open.
+                if !ctx.containers[cnt_idx].open || ctx.containers[cnt_idx].rect.w == 0 {
+                    let menu = parent.map_or(r, |x| ctx.containers[x].rect);
+                    let padding = ctx.px(ctx.style.padding as i32);
+
+                    // Next to the menu with the first entry next to this one.
+                    ctx.containers[cnt_idx].rect = rect(menu.x + menu.w, r.y - padding, 1, 1);
+                    ctx.containers[cnt_idx].open = true;
+                    ctx.bring_to_front(cnt_idx);
+                }
+            }
+
+            // Keep the submenu from closing because the press was outside of it.
+            if ctx.mouse_pressed(MouseButton::Left) {
+                ctx.consume_mouse(MouseButton::Left);
+            }
+        } else if parent.is_some() && ctx.hover_root == parent && ctx.hover_id.is_some() {
+            // Another entry of the menu is hovered.
+            if let Some(cnt_idx) = ctx.get_container(cnt_id, Popup::options()) {
+                ctx.containers[cnt_idx].open = false;
+            }
+        }
+
+        let cnt_idx = ctx.get_container(cnt_id, Popup::options());
+        let open = cnt_idx.is_some_and(|x| ctx.containers[x].open);
+
+        // Moved to the left of the menu or up to stay in the dock area.
+        if let (Some(cnt_idx), Some(menu)) = (cnt_idx, parent.map(|x| ctx.containers[x].rect)) {
+            let r = ctx.containers[cnt_idx].rect;
+            let area = ctx.dock.area;
+            keep_in_area(ctx, cnt_idx, menu.x - r.w, area.y + area.h - r.h);
+        }
+
+        draw_menu_row(ctx, r, &self.text, open || ctx.is_hovered(id), self.enabled);
+
+        let color = if self.enabled {
+            WidgetColor::Text
+        } else {
+            WidgetColor::TextDisabled
+        };
+
+        ctx.draw_icon(Icon::Collapsed, rect(r.x + r.w - r.h, r.y, r.h, r.h), ctx.style.colors[color]);
+
+        if ctx.begin_window(name, Rect::default(), Popup::options()) {
+            menu_body(ctx, contents);
+            ctx.end_window();
+        }
+    }
+}
+
+/// Moves an open menu to `x` if it would leave the dock area on the right
+/// and to `y` if it would leave it at the bottom, like tooltips. It's kept
+/// inside at the top left if it doesn't fit either way.
+fn keep_in_area(ctx: &mut Context, cnt_idx: usize, x: i32, y: i32) {
+    let area = ctx.dock.area;
+    let container = &mut ctx.containers[cnt_idx];
+
+    if !container.open {
+        return;
+    }
+
+    let r = &mut container.rect;
+
+    if area.w > 0 && r.x + r.w > area.x + area.w {
+        r.x = x.max(area.x);
+    }
+
+    if area.h > 0 && r.y + r.h > area.y + area.h {
+        r.y = y.max(area.y);
+    }
+}
+
+/// Shows the entries without space between them and closes
+/// the menu once one of them was clicked.
+fn menu_body(ctx: &mut Context, contents: impl FnOnce(&mut Context)) {
+    let spacing = mem::replace(&mut ctx.style.spacing, 0);
+    contents(ctx);
+    ctx.style.spacing = spacing;
+
+    if ctx.close_menus {
+        ctx.current_container_mut().open = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Window, DrawCommand, MenuItem, Widget, Vec2, vec2};
+    use super::*;
+    use crate::test_util::Handler;
+
+    #[derive(Default)]
+    struct State {
+        wrap: bool,
+        deep: bool,
+        body: bool
+    }
+
+    /// Returns the drawn text and where it was drawn.
+    fn frame(ctx: &mut Context, state: &mut State) -> Vec<(String, Vec2)> {
+        ctx.begin();
+        Window::new("Menus", rect(0, 0, 300, 300)).show(ctx, |ctx| {
+            ctx.button("Target");
+
+            ContextMenu::new("Menu").show(ctx, |ctx| {
+                ctx.menu_item("Copy");
+                ctx.menu_separator();
+                ctx.menu_check("Wrap", &mut state.wrap);
+                MenuItem::new("Paste").enabled(false).draw(ctx);
+
+                Submenu::new("More").show(ctx, |ctx| {
+                    state.deep |= ctx.menu_item("Deep");
+                });
+            });
+
+            ContextMenu::new("Body").on_body().show(ctx, |ctx| {
+                state.body = true;
+                ctx.menu_item("Body item");
+            });
+        });
+        ctx.end();
+
+        ctx.commands()
+            .filter_map(|cmd| match cmd {
+                DrawCommand::Text { text, pos, .. } => Some((text.to_owned(), pos)),
+                _ => None
+            })
+            .collect()
+    }
+
+    fn find(text: &[(String, Vec2)], item: &str) -> Option<Vec2> {
+        text.iter().find(|x| x.0 == item).map(|x| vec2(x.1.x + 2, x.1.y + 2))
+    }
+
+    /// Hovering takes a frame and popups are sized to fit their contents a frame after they are opened.
+    fn settle(ctx: &mut Context, state: &mut State) -> Vec<(String, Vec2)> {
+        frame(ctx, state);
+        frame(ctx, state);
+        frame(ctx, state)
+    }
+
+    fn click(ctx: &mut Context, state: &mut State, pos: Vec2, btn: MouseButton) -> Vec<(String, Vec2)> {
+        ctx.input_mouse_move(pos);
+        frame(ctx, state);
+        frame(ctx, state);
+        ctx.input_mouse_down(pos, btn);
+        frame(ctx, state);
+        ctx.input_mouse_up(pos, btn);
+
+        settle(ctx, state)
+    }
+
+    #[test]
+    fn context_menu() {
+        let mut ctx = Context::new(Handler);
+        let mut state = State::default();
+
+        let text = frame(&mut ctx, &mut state);
+        let target = find(&text, "Target").unwrap();
+        assert!(find(&text, "Copy").is_none());
+
+        // Left clicks don't open the menu.
+        let text = click(&mut ctx, &mut state, target, MouseButton::Left);
+        assert!(find(&text, "Copy").is_none());
+
+        let text = click(&mut ctx, &mut state, target, MouseButton::Right);
+        let wrap = find(&text, "Wrap").unwrap();
+        assert!(find(&text, "Copy").unwrap().y < wrap.y);
+        assert!(!state.body);
+
+        // Disabled items don't close the menu.
+        let paste = find(&text, "Paste").unwrap();
+        let text = click(&mut ctx, &mut state, paste, MouseButton::Left);
+        assert!(find(&text, "Copy").is_some());
+
+        let text = click(&mut ctx, &mut state, wrap, MouseButton::Left);
+        assert!(state.wrap);
+        assert!(find(&text, "Copy").is_none());
+
+        // Hovering a submenu opens it next to the menu.
+        let text = click(&mut ctx, &mut state, target, MouseButton::Right);
+        let more = find(&text, "More").unwrap();
+        ctx.input_mouse_move(more);
+        let text = settle(&mut ctx, &mut state);
+        let deep = find(&text, "Deep").unwrap();
+        assert!(deep.x > more.x);
+
+        // Hovering another entry closes it again.
+        ctx.input_mouse_move(find(&text, "Copy").unwrap());
+        let text = settle(&mut ctx, &mut state);
+        assert!(find(&text, "Deep").is_none());
+
+        // Clicking in the submenu closes both menus.
+        ctx.input_mouse_move(more);
+        settle(&mut ctx, &mut state);
+        let text = click(&mut ctx, &mut state, deep, MouseButton::Left);
+        assert!(state.deep);
+        assert!(find(&text, "Copy").is_none() && find(&text, "Deep").is_none());
+
+        // Opening the menu again doesn't show the submenu that was open.
+        click(&mut ctx, &mut state, target, MouseButton::Right);
+        ctx.input_mouse_move(more);
+        assert!(find(&settle(&mut ctx, &mut state), "Deep").is_some());
+        let text = click(&mut ctx, &mut state, target, MouseButton::Right);
+        assert!(find(&text, "Copy").is_some() && find(&text, "Deep").is_none());
+
+        // Clicking outside of the menu closes it.
+        let text = click(&mut ctx, &mut state, target, MouseButton::Right);
+        assert!(find(&text, "Copy").is_some());
+        let text = click(&mut ctx, &mut state, vec2(250, 250), MouseButton::Left);
+        assert!(find(&text, "Copy").is_none());
+
+        let text = click(&mut ctx, &mut state, vec2(250, 250), MouseButton::Right);
+        assert!(find(&text, "Body item").is_some());
+        assert!(find(&text, "Copy").is_none());
+    }
+
+    #[test]
+    fn item_outside_of_menu() {
+        let mut ctx = Context::new(Handler);
+
+        let frame = |ctx: &mut Context| {
+            ctx.begin();
+            Window::new("Items", rect(0, 0, 300, 300)).show(ctx, |ctx| {
+                ContextMenu::new("Menu").on_body().show(ctx, |ctx| {
+                    ctx.menu_item("Copy");
+                });
+
+                ctx.menu_item("Plain");
+            });
+            ctx.end();
+
+            ctx.commands()
+                .filter_map(|cmd| match cmd {
+                    DrawCommand::Text { text, pos, .. } => Some((text.to_owned(), pos)),
+                    _ => None
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let plain = find(&frame(&mut ctx), "Plain").unwrap();
+        ctx.input_mouse_move(plain);
+        frame(&mut ctx);
+        frame(&mut ctx);
+        ctx.input_mouse_down(plain, MouseButton::Left);
+        frame(&mut ctx);
+        ctx.input_mouse_up(plain, MouseButton::Left);
+
+        // Clicking the item doesn't close the menu opened right after.
+        ctx.input_mouse_down(vec2(250, 250), MouseButton::Right);
+        frame(&mut ctx);
+        ctx.input_mouse_up(vec2(250, 250), MouseButton::Right);
+        frame(&mut ctx);
+        frame(&mut ctx);
+        assert!(find(&frame(&mut ctx), "Copy").is_some());
+    }
+
+    #[test]
+    fn keep_in_area() {
+        let mut ctx = Context::new(Handler);
+        let mut state = State::default();
+        ctx.set_dock_area(rect(0, 0, 280, 280));
+
+        // Opened at the bottom right corner, the menu is flipped to the top left of the mouse.
+        let text = click(&mut ctx, &mut state, vec2(270, 270), MouseButton::Right);
+        let body = find(&text, "Body item").unwrap();
+        assert!(body.x < 270 && body.y < 270);
+
+        // A submenu that doesn't fit on the right of the menu is opened on its left.
+        ctx.set_dock_area(rect(0, 0, 200, 280));
+        let text = click(&mut ctx, &mut state, vec2(250, 250), MouseButton::Left);
+        let target = find(&text, "Target").unwrap();
+        let text = click(&mut ctx, &mut state, target, MouseButton::Right);
+        let more = find(&text, "More").unwrap();
+        ctx.input_mouse_move(more);
+        let deep = find(&settle(&mut ctx, &mut state), "Deep").unwrap();
+        assert!(deep.x < more.x);
+    }
+}
@@ -2,8 +2,14 @@ mod window;
 mod panel;
 mod treenode;
 mod popup;
+mod tooltip;
+mod menu;
+mod dock;
 
 pub use window::*;
 pub use panel::*;
 pub use treenode::*;
 pub use popup::*;
+pub use tooltip::*;
+pub use menu::*;
+pub use dock::*;
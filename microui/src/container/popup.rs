@@ -28,6 +28,7 @@ impl Popup {
             ctx.containers[cnt_idx].open = true;
 
             ctx.bring_to_front(cnt_idx);
+            ctx.close_popups_from(cnt_idx);
         }
     }
 
@@ -36,6 +37,21 @@ impl Popup {
     /// called prior to this.
     #[inline]
     pub fn show(self, ctx: &mut Context, contents: impl FnOnce(&mut Context)) {
+        if ctx.begin_window(self.name, Rect::default(), Self::options()) {
+            contents(ctx);
+            ctx.end_window();
+        }
+    }
+
+    /// The index of the container of the popup if it exists.
+    pub(crate) fn container_index(&self, ctx: &mut Context) -> Option<usize> {
+        let id = ctx.create_id(&self.name);
+
+        ctx.get_container(id, Self::options())
+    }
+
+    /// Popups are sized to fit their contents and closed until they're opened.
+    pub(crate) fn options() -> ContainerOptions {
         let mut options = ContainerOptions::default();
         options.set(ContainerOption::Popup);
         options.set(ContainerOption::AutoSize);
@@ -44,9 +60,6 @@ impl Popup {
         options.set(ContainerOption::NoTitle);
         options.set(ContainerOption::Closed);
 
-        if ctx.begin_window(self.name, Rect::default(), options) {
-            contents(ctx);
-            ctx.end_window();
-        }
+        options
     }
 }
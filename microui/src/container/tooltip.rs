@@ -0,0 +1,194 @@
+use crate::{Context, ContainerOptions, ContainerOption, FrameIdx, Id, TextSizeHandler, rect};
+
+/// The number of frames the mouse has to rest on a widget before its tooltip
+/// is shown, about half a second at 60 frames per second.
+pub const TOOLTIP_DELAY: u32 = 30;
+
+const TOOLTIP_NAME: &str = "!tooltip";
+
+/// The widget the mouse rests on and since when.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TooltipState {
+    pub id: Option<Id>,
+    pub since: FrameIdx,
+    pub delay: u32,
+    /// A tooltip is waiting for its delay in this frame.
+    pub pending: bool
+}
+
+impl Context {
+    /// Shows `text` next to the mouse after it rested on the last widget
+    /// for the tooltip delay. Call this right after the widget.
+    pub fn tooltip(&mut self, text: &str) {
+        self.tooltip_with(|ctx| {
+            let padding = ctx.px(ctx.style.padding as i32);
+            let width = ctx.font_handler.text_width(ctx.style.font, text) + padding * 2;
+
+            ctx.layout_mut().row(&[width], 0);
+            ctx.label(text);
+        });
+    }
+
+    /// Like [`Context::tooltip`], but shows anything `contents` draws. The tooltip
+    /// is sized to fit its contents, so rows shouldn't use relative widths.
+    pub fn tooltip_with(&mut self, contents: impl FnOnce(&mut Context)) {
+        let Some(id) = self.last_id else {
+            return;
+        };
+
+        if self.tooltip.id != Some(id) || !self.is_hovered(id) || self.mouse_any_down() {
+            return;
+        }
+
+        if self.frame - self.tooltip.since < self.tooltip.delay as FrameIdx {
+            self.tooltip.pending = true;
+
+            return;
+        }
+
+        let cnt_id = self.create_id(&TOOLTIP_NAME);
+        let Some(cnt_idx) = self.get_container(cnt_id, ContainerOptions::default()) else {
+            return;
+        };
+
+        // Placed below and to the right of the mouse, so that it doesn't take the hover
+        // away from the widget, unless it would leave the dock area there.
+        let offset = self.px(16);
+        let mut r = self.containers[cnt_idx].rect;
+        let area = self.dock.area;
+
+        r.x = self.mouse_pos.x + offset;
+        r.y = self.mouse_pos.y + offset;
+
+        if area.w > 0 && r.x + r.w > area.x + area.w {
+            r.x = self.mouse_pos.x - offset - r.w;
+        }
+
+        if area.h > 0 && r.y + r.h > area.y + area.h {
+            r.y = self.mouse_pos.y - offset - r.h;
+        }
+
+        if r.w == 0 {
+            r = rect(r.x, r.y, 1, 1);
+        }
+
+        self.containers[cnt_idx].rect = r;
+        self.containers[cnt_idx].open = true;
+
+        if self.containers[cnt_idx].zindex < self.last_zindex {
+            self.bring_to_front(cnt_idx);
+        }
+
+        let mut options = ContainerOptions::default();
+        options.set(ContainerOption::AutoSize);
+        options.set(ContainerOption::NoResize);
+        options.set(ContainerOption::NoScroll);
+        options.set(ContainerOption::NoTitle);
+
+        if self.begin_window(TOOLTIP_NAME, r, options) {
+            contents(self);
+            self.end_window();
+        }
+
+        // The tooltip is a widget of its own, so the caller still refers to the widget.
+        self.last_id = Some(id);
+    }
+
+    /// The number of frames the mouse has to rest on a widget
+    /// before its tooltip is shown. The default is [`TOOLTIP_DELAY`].
+    #[inline]
+    pub fn set_tooltip_delay(&mut self, frames: u32) {
+        self.tooltip.delay = frames;
+    }
+
+    #[inline]
+    pub fn tooltip_delay(&self) -> u32 {
+        self.tooltip.delay
+    }
+
+    /// Returns `true` if a tooltip is waiting for its delay. The application should
+    /// keep drawing frames, even if nothing changed, until it's shown.
+    #[inline]
+    pub fn tooltip_pending(&self) -> bool {
+        self.tooltip.pending
+    }
+
+    /// Restarts the delay when the mouse moves to another widget or a button is held.
+    /// Done in [`Context::end`].
+    pub(crate) fn update_tooltip(&mut self) {
+        if self.tooltip.id != self.hover_id || self.mouse_any_down() {
+            self.tooltip.id = self.hover_id;
+            self.tooltip.since = self.frame;
+        }
+    }
+}
+
+impl Default for TooltipState {
+    fn default() -> Self {
+        Self {
+            id: None,
+            since: 0,
+            delay: TOOLTIP_DELAY,
+            pending: false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Window, DrawCommand, MouseButton, vec2};
+    use super::*;
+    use crate::test_util::Handler;
+
+    /// Returns whether the tooltip was drawn.
+    fn frame(ctx: &mut Context) -> bool {
+        let mut button = None;
+
+        ctx.begin();
+        Window::new("Tooltips", rect(0, 0, 200, 200)).show(ctx, |ctx| {
+            ctx.button("Button");
+            button = ctx.last_id();
+            ctx.tooltip("Tip");
+            assert_eq!(ctx.last_id(), button);
+        });
+        ctx.end();
+
+        ctx.commands().any(|cmd| matches!(cmd, DrawCommand::Text { text: "Tip", .. }))
+    }
+
+    #[test]
+    fn hover_delay() {
+        let mut ctx = Context::new(Handler);
+        ctx.set_tooltip_delay(3);
+        ctx.input_mouse_move(vec2(20, 40));
+
+        let mut frames = 0;
+
+        while !frame(&mut ctx) {
+            assert!(frames < 10, "The tooltip wasn't shown.");
+            frames += 1;
+        }
+
+        assert!(frames > 3);
+        assert!(!ctx.tooltip_pending());
+
+        // The tooltip doesn't take the hover away from the button.
+        assert!(frame(&mut ctx));
+
+        // Pressing the button hides it until the mouse rests again.
+        ctx.input_mouse_down(vec2(20, 40), MouseButton::Left);
+        assert!(!frame(&mut ctx));
+        ctx.input_mouse_up(vec2(20, 40), MouseButton::Left);
+        assert!(!frame(&mut ctx));
+        assert!(ctx.tooltip_pending());
+
+        // Moving away from the button hides it too.
+        ctx.input_mouse_move(vec2(150, 150));
+
+        for _ in 0..10 {
+            assert!(!frame(&mut ctx));
+        }
+
+        assert!(!ctx.tooltip_pending());
+    }
+}
@@ -24,15 +24,17 @@ impl Treenode {
     #[inline]
     pub fn show(self, ctx: &mut Context, contents: impl FnOnce(&mut Context)) {
         if ctx.header_impl(self.label, true, self.expanded) {
+            let indent = ctx.px(ctx.style.indent as i32);
+
             if let Some(layout) = ctx.layout_stack.last_mut() {
-                layout.indent += ctx.style.indent as i32;
+                layout.indent += indent;
                 ctx.id_stack.push(ctx.last_id.unwrap_or_default());
             }
 
             contents(ctx);
 
             if let Some(layout) = ctx.layout_stack.last_mut() {
-                layout.indent -= ctx.style.indent as i32;
+                layout.indent -= indent;
                 ctx.pop_id();
             }
         }
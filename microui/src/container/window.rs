@@ -1,14 +1,19 @@
-use crate::{Context, Rect, Vec2, ContainerOptions, ContainerOption, vec2};
+use std::mem;
+
+use crate::{Context, Rect, Vec2, ContainerOptions, ContainerOption, FontId, vec2};
 
 pub struct Window {
     title: String,
     rect: Rect,
     options: ContainerOptions,
     min_size: Option<Vec2>,
-    max_size: Option<Vec2>
+    max_size: Option<Vec2>,
+    title_font: Option<FontId>
 }
 
 impl Window {
+    /// `rect` is where the window is placed the first time it's shown,
+    /// in logical pixels, see [`Context::set_scale_factor`].
     #[inline]
     pub fn new(title: impl Into<String>, rect: Rect) -> Self {
         Self {
@@ -16,7 +21,8 @@ impl Window {
             rect,
             options: ContainerOptions::default(),
             min_size: None,
-            max_size: None
+            max_size: None,
+            title_font: None
         }
     }
 
@@ -34,6 +40,14 @@ impl Window {
         self
     }
 
+    /// Draws the title with `font` instead of the style's font.
+    #[inline]
+    pub fn title_font(mut self, font: FontId) -> Self {
+        self.title_font = Some(font);
+
+        self
+    }
+
     #[inline]
     pub fn no_frame(mut self) -> Self {
         self.options.set(ContainerOption::NoFrame);
@@ -92,12 +106,22 @@ impl Window {
     }
 
     pub fn show(self, ctx: &mut Context, contents: impl FnOnce(&mut Context)) {
-        if ctx.begin_window(self.title, self.rect, self.options) {
-            if self.min_size.is_some() || self.max_size.is_some() {
+        // The title is only drawn by `begin_window`, so the contents still use the style's font.
+        let font = self.title_font.map(|font| mem::replace(&mut ctx.style.font, font));
+        let rect = ctx.px_rect(self.rect);
+        let open = ctx.begin_window(self.title, rect, self.options);
+
+        if let Some(font) = font {
+            ctx.style.font = font;
+        }
+
+        if open {
+            // Docked windows are sized by the dock instead.
+            if (self.min_size.is_some() || self.max_size.is_some()) && !ctx.current_container().docked {
+                let min = self.min_size.map_or(Vec2::ZERO, |size| vec2(ctx.px(size.x), ctx.px(size.y)));
+                let max = self.max_size.map_or(vec2(i32::MAX, i32::MAX), |size| vec2(ctx.px(size.x), ctx.px(size.y)));
+
                 let container = ctx.current_container_mut();
-    
-                let min = self.min_size.unwrap_or(Vec2::ZERO);
-                let max = self.max_size.unwrap_or(vec2(i32::MAX, i32::MAX));
 
                 container.rect.w = container.rect.w.clamp(min.x, max.x);
                 container.rect.h = container.rect.h.clamp(min.y, max.y);
@@ -0,0 +1,129 @@
+use std::{
+    hash::{Hash, Hasher},
+    collections::hash_map::DefaultHasher,
+    mem
+};
+
+use crate::{Context, Rect};
+
+/// The differences between the commands of the last two frames.
+#[derive(Default, Debug)]
+pub(crate) struct Damage {
+    roots: Vec<RootHash>,
+    prev_roots: Vec<RootHash>,
+    rects: Vec<Rect>,
+    changed: bool
+}
+
+/// The commands of a root container in one frame.
+#[derive(Clone, Copy, Debug)]
+struct RootHash {
+    index: usize,
+    bounds: Rect,
+    hash: u64
+}
+
+impl Context {
+    /// Returns `true` if the commands of the last frame differ from the ones
+    /// of the frame before it, i.e. the screen needs to be redrawn.
+    #[inline]
+    pub fn frame_changed(&self) -> bool {
+        self.damage.changed
+    }
+
+    /// The areas of the screen that changed with the last frame.
+    /// Everything outside of them looks the same as before.
+    #[inline]
+    pub fn dirty_rects(&self) -> &[Rect] {
+        &self.damage.rects
+    }
+
+    /// Hashes the commands of every root container and compares
+    /// them to the last frame. Done at the end of [`Context::end`].
+    pub(crate) fn update_damage(&mut self) {
+        let damage = &mut self.damage;
+        mem::swap(&mut damage.roots, &mut damage.prev_roots);
+        damage.roots.clear();
+        damage.rects.clear();
+        damage.changed = false;
+
+        for (i, index) in self.root_list.iter().copied().enumerate() {
+            let container = &self.containers[index];
+
+            let mut hasher = DefaultHasher::new();
+            // Include the z-order so that overlapping windows are redrawn when it changes.
+            i.hash(&mut hasher);
+            container.bounds.hash(&mut hasher);
+
+            if let (Some(head), Some(tail)) = (container.head, container.tail) {
+                for cmd in self.command_list.get(head + 1..tail).unwrap_or_default() {
+                    self.draw_command(*cmd).hash(&mut hasher);
+                }
+            }
+
+            self.damage.roots.push(RootHash {
+                index,
+                bounds: container.bounds,
+                hash: hasher.finish()
+            });
+        }
+
+        let damage = &mut self.damage;
+
+        for root in &damage.roots {
+            match damage.prev_roots.iter().find(|x| x.index == root.index) {
+                Some(prev) if prev.hash == root.hash => continue,
+                Some(prev) => damage.rects.extend([prev.bounds, root.bounds]),
+                None => damage.rects.push(root.bounds)
+            }
+
+            damage.changed = true;
+        }
+
+        for prev in &damage.prev_roots {
+            if !damage.roots.iter().any(|x| x.index == prev.index) {
+                damage.rects.push(prev.bounds);
+                damage.changed = true;
+            }
+        }
+
+        damage.rects.retain(|x| !x.is_empty());
+        damage.rects.dedup();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Context, Window, rect};
+    use crate::test_util::Handler;
+
+    #[test]
+    fn dirty_rects() {
+        let mut ctx = Context::new(Handler);
+
+        let frame = |ctx: &mut Context, label: &str| {
+            ctx.begin();
+            Window::new("A", rect(10, 10, 100, 100)).show(ctx, |ctx| ctx.label(label));
+            Window::new("B", rect(200, 10, 100, 100)).show(ctx, |ctx| ctx.label("B"));
+            ctx.end();
+        };
+
+        frame(&mut ctx, "A");
+        assert!(ctx.frame_changed());
+        assert_eq!(ctx.dirty_rects(), [rect(9, 9, 102, 102), rect(199, 9, 102, 102)]);
+
+        frame(&mut ctx, "A");
+        assert!(!ctx.frame_changed());
+        assert!(ctx.dirty_rects().is_empty());
+
+        frame(&mut ctx, "Changed");
+        assert!(ctx.frame_changed());
+        assert_eq!(ctx.dirty_rects(), [rect(9, 9, 102, 102)]);
+
+        // Closing a window damages the area it covered.
+        ctx.begin();
+        Window::new("A", rect(10, 10, 100, 100)).show(&mut ctx, |ctx| ctx.label("Changed"));
+        ctx.end();
+        assert_eq!(ctx.dirty_rects(), [rect(199, 9, 102, 102)]);
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+
+/// One of the lists, stacks and pools of a [`Context`](crate::Context).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stack {
+    CommandList,
+    RootList,
+    ContainerStack,
+    ClipStack,
+    IdStack,
+    LayoutStack,
+    ContainerPool,
+    TreenodePool,
+    FocusList
+}
+
+/// A misuse of the API that is collected instead of panicking when
+/// [`ContextConfig::diagnostics`](crate::ContextConfig::diagnostics) is enabled.
+/// See [`Context::errors`](crate::Context::errors).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum UiError {
+    /// The stack ran out of space and the items that didn't fit were dropped.
+    StackOverflow(Stack),
+    /// The stack wasn't empty at the end of the frame. `container` is the name
+    /// of the innermost window, popup or panel that wasn't ended, if any.
+    Unbalanced {
+        stack: Stack,
+        container: Option<String>
+    },
+    /// A widget was drawn outside of a window or a popup.
+    OutsideRoot
+}
+
+impl Stack {
+    /// The name of the [`ContextConfig`](crate::ContextConfig) method that sets its size.
+    pub fn setting(self) -> &'static str {
+        match self {
+            Self::CommandList => "command_list_size",
+            Self::RootList => "root_list_size",
+            Self::ContainerStack => "container_stack_size",
+            Self::ClipStack => "clip_stack_size",
+            Self::IdStack => "id_stack_size",
+            Self::LayoutStack => "layout_stack_size",
+            Self::ContainerPool => "container_pool_size",
+            Self::TreenodePool => "treenode_pool_size",
+            Self::FocusList => "focus_list_size"
+        }
+    }
+}
+
+impl fmt::Display for UiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StackOverflow(stack) => write!(
+                f,
+                "Ran out of space in the {:?}. Raise the limit with ContextConfig::{}().",
+                stack,
+                stack.setting()
+            ),
+            Self::Unbalanced { stack, container: Some(name) } => write!(
+                f,
+                "The {:?} is not empty at the end of the frame. You probably forgot to end \"{}\".",
+                stack,
+                name
+            ),
+            Self::Unbalanced { stack, container: None } => write!(
+                f,
+                "The {:?} is not empty at the end of the frame.",
+                stack
+            ),
+            Self::OutsideRoot => f.write_str("Widgets must be drawn inside of a window or a popup.")
+        }
+    }
+}
+
+impl std::error::Error for UiError {}
@@ -1,10 +1,12 @@
-#[derive(Clone, Copy, PartialEq, Default, Debug)]
+use std::hash::{Hash, Hasher};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
 pub struct Vec2 {
     pub x: i32,
     pub y: i32
 }
 
-#[derive(Clone, Copy, PartialEq, Default, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
 pub struct Rect {
     pub x: i32,
     pub y: i32,
@@ -12,6 +14,20 @@ pub struct Rect {
     pub h: i32,
 }
 
+/// A part of an image in texture coordinates that go from 0 to 1.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct UvRect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32
+}
+
+/// An angle in radians that goes clockwise from the positive x axis,
+/// since the y axis points down.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Default, Debug)]
+pub struct Angle(pub f32);
+
 #[derive(Clone, Copy)]
 pub enum Clip {
     None,
@@ -33,6 +49,40 @@ impl Vec2 {
     pub const ZERO: Self = vec2(0, 0);
 }
 
+impl UvRect {
+    pub const FULL: Self = Self { x: 0., y: 0., w: 1., h: 1. };
+}
+
+// Texture coordinates are never NaN.
+impl Eq for UvRect {}
+
+impl Hash for UvRect {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for x in [self.x, self.y, self.w, self.h] {
+            x.to_bits().hash(state);
+        }
+    }
+}
+
+impl Angle {
+    pub const ZERO: Self = Self(0.);
+    pub const FULL: Self = Self(std::f32::consts::TAU);
+
+    #[inline]
+    pub fn degrees(degrees: f32) -> Self {
+        Self(degrees.to_radians())
+    }
+}
+
+// Angles are never NaN.
+impl Eq for Angle {}
+
+impl Hash for Angle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
 impl Rect {
     pub const UNCLIPPED: Self = rect(0, 0, 0x1000000, 0x1000000);
 
@@ -69,6 +119,45 @@ impl Rect {
         }
     }
 
+    /// The smallest rect that contains both. Empty rects are ignored.
+    pub fn union(&self, rhs: Self) -> Self {
+        if self.is_empty() {
+            return rhs;
+        }
+
+        if rhs.is_empty() {
+            return *self;
+        }
+
+        let x1 = self.x.min(rhs.x);
+        let y1 = self.y.min(rhs.y);
+        let x2 = Ord::max(self.x + self.w, rhs.x + rhs.w);
+        let y2 = Ord::max(self.y + self.h, rhs.y + rhs.h);
+
+        rect(x1, y1, x2 - x1, y2 - y1)
+    }
+
+    /// The smallest rect that contains all of the points.
+    pub fn bounding(points: impl IntoIterator<Item = Vec2>) -> Self {
+        let mut points = points.into_iter();
+
+        let Some(first) = points.next() else {
+            return Self::default();
+        };
+
+        let (min, max) = points.fold((first, first), |(min, max), p| (
+            vec2(min.x.min(p.x), min.y.min(p.y)),
+            vec2(max.x.max(p.x), max.y.max(p.y))
+        ));
+
+        rect(min.x, min.y, max.x - min.x, max.y - min.y)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.w <= 0 || self.h <= 0
+    }
+
     pub fn clip(&self, rhs: Self) -> Clip {
         if rhs.x > self.x + self.w || rhs.x + rhs.w < self.x ||
             rhs.y > self.y + self.h || rhs.y + rhs.h < self.y { 
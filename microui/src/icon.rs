@@ -0,0 +1,159 @@
+use std::{cmp, collections::HashMap};
+
+use crate::{ImageId, Rect, UvRect, Vec2, shape, vec2};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Icon {
+    None,
+    Close,
+    Check,
+    Collapsed,
+    Expanded,
+    Resize,
+    /// An icon defined by the application, i.e. in an [`IconAtlas`].
+    Custom(u32)
+}
+
+/// Icons packed into one image that has been given to the renderer.
+/// Renderers draw the icons it contains with their color as the tint,
+/// so they should be white on a transparent background.
+#[derive(Clone, PartialEq, Debug)]
+pub struct IconAtlas {
+    image: ImageId,
+    size: Vec2,
+    icons: HashMap<Icon, Rect>
+}
+
+impl Icon {
+    /// Draws the built-in icons with rectangles only, so that they show up on
+    /// any renderer without an icon font. Calls `f` with every rect to fill.
+    /// Custom icons are skipped.
+    pub fn fallback(self, rect: Rect, mut f: impl FnMut(Rect)) {
+        let cx = rect.x + rect.w / 2;
+        let cy = rect.y + rect.h / 2;
+        let e = cmp::max(cmp::min(rect.w, rect.h) / 4, 2);
+
+        match self {
+            Self::Close => {
+                shape::line(vec2(cx - e + 1, cy - e + 1), vec2(cx + e - 1, cy + e - 1), 2, &mut f);
+                shape::line(vec2(cx + e - 1, cy - e + 1), vec2(cx - e + 1, cy + e - 1), 2, &mut f);
+            },
+            Self::Check => {
+                let b = vec2(cx - e / 3 + 1, cy + e * 2 / 3);
+
+                shape::line(vec2(cx - e + 1, cy), b, 2, &mut f);
+                shape::line(b, vec2(cx + e, cy - e * 2 / 3), 2, &mut f);
+            },
+            Self::Collapsed => {
+                for i in 0..e {
+                    f(Rect { x: cx - e / 2 + i, y: cy - e + i, w: 1, h: (e - i) * 2 });
+                }
+            },
+            Self::Expanded => {
+                for i in 0..e {
+                    f(Rect { x: cx - e + i, y: cy - e / 2 + i, w: (e - i) * 2, h: 1 });
+                }
+            },
+            Self::Resize => {
+                for i in 0..e * 2 {
+                    f(Rect { x: cx + e - i - 1, y: cy - e + i, w: i + 1, h: 1 });
+                }
+            },
+            Self::None | Self::Custom(_) => {}
+        }
+    }
+}
+
+impl IconAtlas {
+    /// `width` and `height` are the size of the image in pixels.
+    #[inline]
+    pub fn new(image: ImageId, width: i32, height: i32) -> Self {
+        Self {
+            image,
+            size: vec2(width, height),
+            icons: HashMap::new()
+        }
+    }
+
+    /// Adds an icon that is drawn from the `src` pixels of the image.
+    /// This can also replace the built-in icons.
+    #[inline]
+    pub fn icon(mut self, icon: Icon, src: Rect) -> Self {
+        self.insert(icon, src);
+
+        self
+    }
+
+    #[inline]
+    pub fn insert(&mut self, icon: Icon, src: Rect) {
+        self.icons.insert(icon, src);
+    }
+
+    #[inline]
+    pub fn contains(&self, icon: Icon) -> bool {
+        self.icons.contains_key(&icon)
+    }
+
+    #[inline]
+    pub fn image(&self) -> ImageId {
+        self.image
+    }
+
+    /// Where the icon is drawn when it's centered in `rect` at its original
+    /// size and the part of the image to draw there. Returns `None` if the
+    /// atlas doesn't contain the icon.
+    pub fn locate(&self, icon: Icon, rect: Rect) -> Option<(Rect, UvRect)> {
+        let src = *self.icons.get(&icon)?;
+
+        let dst = Rect {
+            x: rect.x + (rect.w - src.w) / 2,
+            y: rect.y + (rect.h - src.h) / 2,
+            w: src.w,
+            h: src.h
+        };
+
+        let uv = UvRect {
+            x: src.x as f32 / self.size.x as f32,
+            y: src.y as f32 / self.size.y as f32,
+            w: src.w as f32 / self.size.x as f32,
+            h: src.h as f32 / self.size.y as f32
+        };
+
+        Some((dst, uv))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rect;
+
+    #[test]
+    fn fallback_inside_rect() {
+        let area = rect(10, 20, 24, 24);
+
+        for icon in [Icon::Close, Icon::Check, Icon::Collapsed, Icon::Expanded, Icon::Resize] {
+            let mut rects = Vec::new();
+            icon.fallback(area, |r| rects.push(r));
+
+            assert!(!rects.is_empty(), "{:?} has no rects", icon);
+            assert!(rects.iter().all(|r| r.intersect(area) == *r), "{:?} is outside of the rect", icon);
+        }
+
+        let mut count = 0;
+        Icon::Custom(1).fallback(area, |_| count += 1);
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn atlas() {
+        let atlas = IconAtlas::new(ImageId(3), 64, 32)
+            .icon(Icon::Custom(7), rect(16, 0, 16, 16));
+
+        let (dst, uv) = atlas.locate(Icon::Custom(7), rect(0, 0, 24, 24)).unwrap();
+        assert_eq!(dst, rect(4, 4, 16, 16));
+        assert_eq!(uv, UvRect { x: 0.25, y: 0., w: 0.25, h: 0.5 });
+
+        assert!(atlas.locate(Icon::Close, rect(0, 0, 24, 24)).is_none());
+    }
+}
@@ -0,0 +1,153 @@
+use std::{mem, fmt, str::FromStr};
+
+macro_rules! keys {
+    ($($name:ident),* $(,)?) => {
+        /// A physical key. Left and right modifier keys are reported
+        /// as a single key, e.g. both shift keys are [`Key::Shift`].
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        #[repr(u8)]
+        pub enum Key {
+            $($name),*
+        }
+
+        impl Key {
+            pub const ALL: [Key; mem::variant_count::<Key>()] = [$(Key::$name),*];
+
+            /// The names of the variants in the order of [`Key::ALL`].
+            const NAMES: [&'static str; mem::variant_count::<Key>()] = [$(stringify!($name)),*];
+        }
+    };
+}
+
+keys! {
+    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
+
+    A, B, C, D, E, F, G, H, I, J, K, L, M,
+    N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24,
+
+    Escape, Tab, Space, Return, Backspace, Delete, Insert,
+    Home, End, PageUp, PageDown,
+    Left, Right, Up, Down,
+
+    Shift, Ctrl, Alt, Logo,
+    CapsLock, NumLock, ScrollLock, PrintScreen, Pause, Apps, Compose,
+
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4,
+    Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    NumpadAdd, NumpadSubtract, NumpadMultiply, NumpadDivide,
+    NumpadDecimal, NumpadComma, NumpadEnter, NumpadEquals,
+
+    Apostrophe, Asterisk, At, Backslash, Caret, Colon, Comma, Equals,
+    Grave, LBracket, RBracket, Minus, Period, Plus, Semicolon, Slash, Underline,
+
+    Copy, Cut, Paste,
+
+    AbntC1, AbntC2, Ax, Convert, Kana, Kanji, NoConvert, Oem102, Yen,
+    Calculator, Mail, MediaSelect, MediaStop, Mute, MyComputer,
+    NavigateForward, NavigateBackward, NextTrack, PlayPause, PrevTrack,
+    VolumeDown, VolumeUp, Power, Sleep, Wake, Stop, Sysrq, Unlabeled,
+    WebBack, WebFavorites, WebForward, WebHome, WebRefresh, WebSearch, WebStop
+}
+
+/// The keys were flags of the modifiers and a few editing keys before
+/// [`Key`] replaced them. Each of them has a variant of the same name in it.
+#[deprecated(note = "Use `Key` instead.")]
+pub type ModKey = Key;
+
+const KEY_STATE_WORDS: usize = Key::ALL.len().div_ceil(64);
+
+/// A set of [`Key`]s.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub(crate) struct KeyState([u64; KEY_STATE_WORDS]);
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseKeyError;
+
+impl KeyState {
+    #[inline(always)]
+    pub fn is_set(&self, key: Key) -> bool {
+        let (word, bit) = Self::position(key);
+        self.0[word] & bit == bit
+    }
+
+    #[inline(always)]
+    pub fn is_unset(&self, key: Key) -> bool {
+        !self.is_set(key)
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, key: Key) {
+        let (word, bit) = Self::position(key);
+        self.0[word] |= bit;
+    }
+
+    #[inline(always)]
+    pub fn unset(&mut self, key: Key) {
+        let (word, bit) = Self::position(key);
+        self.0[word] &= !bit;
+    }
+
+    #[inline(always)]
+    fn position(key: Key) -> (usize, u64) {
+        let index = key as usize;
+
+        (index / 64, 1 << (index % 64))
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    /// Parses the name of a variant as printed by its `Debug` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Key::NAMES.iter()
+            .position(|name| *name == s)
+            .map(|index| Key::ALL[index])
+            .ok_or(ParseKeyError)
+    }
+}
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Unknown key name.")
+    }
+}
+
+impl std::error::Error for ParseKeyError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state() {
+        let mut state = KeyState::default();
+        state.set(Key::A);
+        state.set(Key::WebStop);
+
+        assert!(state.is_set(Key::A) && state.is_set(Key::WebStop));
+        assert!(state.is_unset(Key::B));
+
+        state.unset(Key::WebStop);
+        assert!(state.is_unset(Key::WebStop));
+
+        assert_eq!("PageDown".parse(), Ok(Key::PageDown));
+        assert_eq!("Pagedown".parse::<Key>(), Err(ParseKeyError));
+
+        for key in Key::ALL {
+            assert_eq!(format!("{:?}", key).parse(), Ok(key));
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn mod_key() {
+        let mut state = KeyState::default();
+        state.set(ModKey::Ctrl);
+
+        assert!(state.is_set(Key::Ctrl));
+    }
+}
@@ -3,22 +3,46 @@
 
 pub mod const_vec;
 pub mod widget;
+pub mod shape;
+pub mod text;
 mod container;
 mod text_buf;
 mod geometry;
 mod style;
 mod id;
+mod recording;
+mod clipboard;
+mod key;
+mod state;
+mod config;
+mod bounded_vec;
+mod error;
+mod command;
+mod damage;
+mod icon;
+#[cfg(test)]
+mod test_util;
 
 pub use geometry::*;
 pub use style::*;
 pub use id::Id;
 pub use text_buf::TextBuf;
+pub use recording::*;
+pub use clipboard::*;
+pub use key::*;
+pub use state::*;
+pub use config::ContextConfig;
+pub use error::*;
+pub use icon::*;
+pub use command::{DrawCommand, Commands};
 pub use widget::{textbox, dropdown, *};
 pub use container::*;
 
-use std::{ptr, cmp, mem, ops::Range, hash::Hash};
+use std::{ptr, cmp, mem, iter, ops::Range, hash::Hash};
 
-use const_vec::{ConstVec, ConstStr};
+use const_vec::ConstStr;
+use bounded_vec::BoundedVec;
+use damage::Damage;
 
 pub const COMMAND_LIST_SIZE: usize = 4096;
 pub const ROOT_LIST_SIZE: usize = 32;
@@ -28,6 +52,10 @@ pub const ID_STACK_SIZE: usize = 32;
 pub const LAYOUT_STACK_SIZE: usize = 16;
 pub const CONTAINER_POOL_SIZE: usize = 48;
 pub const TREENODE_POOL_SIZE: usize = 48;
+pub const FOCUS_LIST_SIZE: usize = 256;
+/// The number of frames after which containers and tree nodes that weren't
+/// shown can be replaced in pools without a capacity.
+pub const POOL_EVICT_FRAMES: u64 = 600;
 pub const MAX_WIDTHS: usize = 16;
 pub const MAX_FMT: usize = 127;
 pub const MAX_TEXT_STORE: usize = 1024;
@@ -71,10 +99,12 @@ macro_rules! impl_flags {
 pub struct Context {
     pub draw_frame: DrawFrameFn,
     pub style: Style,
-    font_handler: Box<dyn TextSizeHandler>,
+    font_handler: ScaledTextSizeHandler,
+    clipboard: Box<dyn Clipboard>,
     cursor_icon: Option<CursorIcon>,
     hover_id: Option<Id>,
     focus_id: Option<Id>,
+    key_focus: bool,
     last_id: Option<Id>,
     last_rect: Rect,
     last_zindex: isize,
@@ -83,37 +113,42 @@ pub struct Context {
     hover_root: Option<usize>,
     next_hover_root: Option<usize>,
     scroll_target: Option<usize>,
+    top_popup: Option<usize>,
+    tooltip: TooltipState,
+    /// Set by a menu item that was clicked to close the menus it's in.
+    close_menus: bool,
     number_edit_buf: ConstStr<MAX_FMT>,
     number_edit_id: Option<Id>,
-    command_list: ConstVec<Command, COMMAND_LIST_SIZE>,
-    root_list: ConstVec<usize, ROOT_LIST_SIZE>,
-    container_stack: ConstVec<usize, CONTAINER_STACK_SIZE>,
-    clip_stack: ConstVec<Rect, CLIP_STACK_SIZE>,
-    id_stack: ConstVec<Id, ID_STACK_SIZE>,
-    layout_stack: ConstVec<Layout, LAYOUT_STACK_SIZE>,
-    container_pool: ConstVec<PoolItem, CONTAINER_POOL_SIZE>,
-    containers: ConstVec<Container, CONTAINER_POOL_SIZE>,
-    treenode_pool: ConstVec<PoolItem, TREENODE_POOL_SIZE>,
+    text_edit: textbox::EditState,
+    text_edit_id: Option<Id>,
+    command_list: BoundedVec<Command>,
+    text_arena: String,
+    point_arena: Vec<Vec2>,
+    root_list: BoundedVec<usize>,
+    container_stack: BoundedVec<usize>,
+    clip_stack: BoundedVec<Rect>,
+    id_stack: BoundedVec<Id>,
+    layout_stack: BoundedVec<Layout>,
+    container_pool: BoundedVec<PoolItem>,
+    containers: BoundedVec<Container>,
+    treenode_pool: BoundedVec<PoolItem>,
+    focus_list: BoundedVec<Id>,
+    diagnostics: bool,
+    errors: Vec<UiError>,
+    detached_layout: Layout,
+    damage: Damage,
+    dock: Dock,
     mouse_pos: Vec2,
     last_mouse_pos: Vec2,
     mouse_delta: Vec2,
     scroll_delta: Vec2,
     mouse_down: MouseState,
     mouse_pressed: MouseState,
-    key_down: ModKeyState,
-    key_pressed: ModKeyState,
-    text_input: ConstStr<MAX_TEXT_STORE>
-}
-
-#[derive(Clone, Copy, PartialEq, Debug)]
-#[repr(u8)]
-pub enum Icon {
-    None,
-    Close,
-    Check,
-    Collapsed,
-    Expanded,
-    Resize
+    key_down: KeyState,
+    key_pressed: KeyState,
+    text_input: ConstStr<MAX_TEXT_STORE>,
+    recording: Option<InputRecording>,
+    recording_start: FrameIdx
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -157,23 +192,17 @@ pub enum MouseButton {
     Middle = 1 << 2
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
-#[repr(u8)]
-pub enum ModKey {
-    Shift = 1 << 0,
-    Ctrl = 1 << 1,
-    Alt = 1 << 2,
-    Backspace = 1 << 3,
-    Return = 1 << 4
-}
 
 impl_flags!(pub ContainerOptions, ContainerOption, u16);
 impl_flags!(MouseState, MouseButton, u8);
-impl_flags!(ModKeyState, ModKey, u8);
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
 pub struct FontId(pub u32);
 
+/// An image that has been given to the renderer, e.g. with `Shell::create_image` in `microui-app`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+pub struct ImageId(pub u32);
+
 #[derive(Clone, Default)]
 pub struct Layout {
     body: Rect,
@@ -203,6 +232,17 @@ pub struct Container {
     pub scroll: Vec2,
     pub zindex: isize,
     pub open: bool,
+    name: String,
+    bounds: Rect,
+    docked: bool,
+    /// Whether it was last shown as a popup. Popups aren't saved.
+    popup: bool,
+    /// Whether it's a window rather than a panel.
+    window: bool,
+    /// The id of the root container a popup was opened in. Clicking in a
+    /// popup doesn't close the popups it was opened from. It's not an index,
+    /// since the pool may reuse that for another container.
+    parent: Option<Id>,
     head: Option<usize>,
     tail: Option<usize>
 }
@@ -212,6 +252,13 @@ pub trait TextSizeHandler {
     fn text_height(&self, id: FontId) -> i32;
 }
 
+/// Multiplies the sizes of the text, measured in logical pixels by the
+/// handler, by the scale factor of the [`Context`].
+struct ScaledTextSizeHandler {
+    handler: Box<dyn TextSizeHandler>,
+    scale_factor: f32
+}
+
 pub trait CommandHandler {
     fn clip_cmd(&mut self, rect: Rect);
     fn rect_cmd(&mut self, rect: Rect, color: Color);
@@ -220,24 +267,91 @@ pub trait CommandHandler {
         font: FontId,
         pos: Vec2,
         color: Color,
-        text: String
+        text: &str
     );
+    /// Draws the built-in icons with [`Icon::fallback`] by default.
     fn icon_cmd(
         &mut self,
         id: Icon,
         rect: Rect,
         color: Color
-    );
+    ) {
+        id.fallback(rect, |r| self.rect_cmd(r, color));
+    }
+    /// Draws the `uv` part of the image stretched over `rect` and multiplied by `tint`.
+    /// Images are ignored by default.
+    fn image_cmd(
+        &mut self,
+        _id: ImageId,
+        _rect: Rect,
+        _uv: UvRect,
+        _tint: Color
+    ) { }
+    /// Draws a line with square ends. The shapes are
+    /// drawn with the rects from [`shape`] by default.
+    fn line_cmd(
+        &mut self,
+        a: Vec2,
+        b: Vec2,
+        thickness: i32,
+        color: Color
+    ) {
+        shape::line(a, b, thickness, |r| self.rect_cmd(r, color));
+    }
+    fn rounded_rect_cmd(
+        &mut self,
+        rect: Rect,
+        radius: i32,
+        color: Color
+    ) {
+        shape::rounded_rect(rect, radius, |r| self.rect_cmd(r, color));
+    }
+    fn circle_cmd(
+        &mut self,
+        center: Vec2,
+        radius: i32,
+        color: Color
+    ) {
+        shape::circle(center, radius, |r| self.rect_cmd(r, color));
+    }
+    /// Draws the outline of a circle clockwise from `start` to `end`.
+    fn arc_cmd(
+        &mut self,
+        center: Vec2,
+        radius: i32,
+        start: Angle,
+        end: Angle,
+        thickness: i32,
+        color: Color
+    ) {
+        shape::arc(center, radius, start, end, thickness, |r| self.rect_cmd(r, color));
+    }
+    fn triangle_cmd(
+        &mut self,
+        points: [Vec2; 3],
+        color: Color
+    ) {
+        shape::polygon(&points, |r| self.rect_cmd(r, color));
+    }
+    /// Fills a convex polygon.
+    fn polygon_cmd(
+        &mut self,
+        points: &[Vec2],
+        color: Color
+    ) {
+        shape::polygon(points, |r| self.rect_cmd(r, color));
+    }
 }
 
 #[derive(Clone, Copy, Default, PartialEq, Debug)]
 pub struct WidgetInteraction {
     options: ContainerOptions,
     cursor: Option<CursorIcon>,
-    retain_cursor_focus: bool
+    retain_cursor_focus: bool,
+    focusable: bool
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 enum Command {
     Jump(usize),
     Clip(Rect),
@@ -249,41 +363,116 @@ enum Command {
         font: FontId,
         pos: Vec2,
         color: Color,
-        text: String
+        text: ArenaRange
     },
     Icon {
         id: Icon,
         rect: Rect,
         color: Color
+    },
+    Image {
+        id: ImageId,
+        rect: Rect,
+        uv: UvRect,
+        tint: Color
+    },
+    Line {
+        a: Vec2,
+        b: Vec2,
+        thickness: i32,
+        color: Color
+    },
+    RoundedRect {
+        rect: Rect,
+        radius: i32,
+        color: Color
+    },
+    Circle {
+        center: Vec2,
+        radius: i32,
+        color: Color
+    },
+    Arc {
+        center: Vec2,
+        radius: i32,
+        start: Angle,
+        end: Angle,
+        thickness: i32,
+        color: Color
+    },
+    Triangle {
+        points: [Vec2; 3],
+        color: Color
+    },
+    Polygon {
+        points: ArenaRange,
+        color: Color
     }
 }
 
+/// The bytes of a string in `Context::text_arena`
+/// or the points of a polygon in `Context::point_arena`.
+#[derive(Clone, Copy, Debug)]
+struct ArenaRange {
+    start: usize,
+    end: usize
+}
+
 #[derive(Clone, Copy, Default)]
 struct PoolItem {
     id: Id,
     last_update: FrameIdx
 }
 
-pub fn draw_frame(ctx: &mut Context, rect: Rect, color_id: WidgetColor) {
-    ctx.draw_rect(rect, ctx.style.colors[color_id]);
+pub fn draw_frame(ctx: &mut Context, r: Rect, color_id: WidgetColor) {
+    let color = ctx.style.colors[color_id];
+    let radius = ctx.px(ctx.style.corner_radius as i32);
+    let border_width = ctx.px_line(ctx.style.border_width as i32);
+    let border_color = ctx.style.colors[WidgetColor::Border];
 
-    if matches!(
+    let has_border = border_color.a != 0 && border_width > 0 && !matches!(
         color_id,
         WidgetColor::ScrollBase |
         WidgetColor::ScrollThumb |
         WidgetColor::TitleBackground
-    ) {
+    );
+
+    if radius == 0 {
+        ctx.draw_rect(r, color);
+
+        if has_border {
+            for i in 1..=border_width {
+                ctx.draw_box(r.expand(i), border_color);
+            }
+        }
+
         return;
     }
 
-    let border_color = ctx.style.colors[WidgetColor::Border];
-    if border_color.a != 0 {
-        ctx.draw_box(rect.expand(1), border_color);
+    if has_border {
+        ctx.draw_rounded_rect(r.expand(border_width), radius + border_width, border_color);
+    }
+
+    ctx.draw_rounded_rect(r, radius, color);
+
+    // Title bars are only rounded at the top, where they meet the corners of the window.
+    if matches!(color_id, WidgetColor::TitleBackground) {
+        let h = cmp::min(radius, r.h / 2);
+        ctx.draw_rect(rect(r.x, r.y + r.h - h, r.w, h), color);
     }
 }
 
 impl Context {
+    /// Creates a context with the default [`ContextConfig`].
+    #[inline]
     pub fn new(font_handler: impl TextSizeHandler + 'static) -> Box<Self> {
+        Self::with_config(font_handler, ContextConfig::default())
+    }
+
+    pub fn with_config(
+        font_handler: impl TextSizeHandler + 'static,
+        config: ContextConfig
+    ) -> Box<Self> {
         let mut ctx = Box::<Self>::new_zeroed();
         let ctx_ptr = ctx.as_mut_ptr();
 
@@ -293,7 +482,51 @@ impl Context {
         unsafe {
             ptr::addr_of_mut!(
                 (*ctx_ptr).font_handler
-            ).write(Box::new(font_handler));
+            ).write(ScaledTextSizeHandler {
+                handler: Box::new(font_handler),
+                scale_factor: 1.
+            });
+
+            ptr::addr_of_mut!(
+                (*ctx_ptr).clipboard
+            ).write(Box::<MemoryClipboard>::default());
+
+            ptr::addr_of_mut!(
+                (*ctx_ptr).recording
+            ).write(None);
+
+            ptr::addr_of_mut!(
+                (*ctx_ptr).dock
+            ).write(Dock::default());
+
+            ptr::addr_of_mut!(
+                (*ctx_ptr).errors
+            ).write(Vec::new());
+
+            ptr::addr_of_mut!(
+                (*ctx_ptr).text_arena
+            ).write(String::new());
+
+            ptr::addr_of_mut!(
+                (*ctx_ptr).point_arena
+            ).write(Vec::new());
+
+            ptr::addr_of_mut!(
+                (*ctx_ptr).damage
+            ).write(Damage::default());
+
+            let lenient = config.diagnostics;
+
+            ptr::addr_of_mut!((*ctx_ptr).command_list).write(BoundedVec::new(config.command_list, Stack::CommandList, lenient));
+            ptr::addr_of_mut!((*ctx_ptr).root_list).write(BoundedVec::new(config.root_list, Stack::RootList, lenient));
+            ptr::addr_of_mut!((*ctx_ptr).container_stack).write(BoundedVec::new(config.container_stack, Stack::ContainerStack, lenient));
+            ptr::addr_of_mut!((*ctx_ptr).clip_stack).write(BoundedVec::new(config.clip_stack, Stack::ClipStack, lenient));
+            ptr::addr_of_mut!((*ctx_ptr).id_stack).write(BoundedVec::new(config.id_stack, Stack::IdStack, lenient));
+            ptr::addr_of_mut!((*ctx_ptr).layout_stack).write(BoundedVec::new(config.layout_stack, Stack::LayoutStack, lenient));
+            ptr::addr_of_mut!((*ctx_ptr).container_pool).write(BoundedVec::new(config.container_pool, Stack::ContainerPool, lenient));
+            ptr::addr_of_mut!((*ctx_ptr).containers).write(BoundedVec::new(config.container_pool, Stack::ContainerPool, lenient));
+            ptr::addr_of_mut!((*ctx_ptr).treenode_pool).write(BoundedVec::new(config.treenode_pool, Stack::TreenodePool, lenient));
+            ptr::addr_of_mut!((*ctx_ptr).focus_list).write(BoundedVec::new(config.focus_list, Stack::FocusList, lenient));
         }
 
         let mut ptr = unsafe { &mut *ctx_ptr };
@@ -302,6 +535,7 @@ impl Context {
         ptr.style = Style::default();
         ptr.hover_id = None;
         ptr.focus_id = None;
+        ptr.key_focus = false;
         ptr.last_id = None;
         ptr.last_rect = Rect::default();
         ptr.last_zindex = 0;
@@ -310,30 +544,142 @@ impl Context {
         ptr.hover_root = None;
         ptr.next_hover_root = None;
         ptr.scroll_target = None;
+        ptr.top_popup = None;
+        ptr.tooltip = TooltipState::default();
+        ptr.close_menus = false;
         ptr.number_edit_id = None;
+        ptr.text_edit = textbox::EditState::default();
+        ptr.text_edit_id = None;
         ptr.mouse_pos = Vec2::ZERO;
         ptr.last_mouse_pos = Vec2::ZERO;
         ptr.mouse_delta = Vec2::ZERO;
         ptr.scroll_delta = Vec2::ZERO;
         ptr.mouse_down = MouseState::default();
         ptr.mouse_pressed = MouseState::default();
-        ptr.key_down = ModKeyState::default();
-        ptr.key_pressed = ModKeyState::default();
-
-        ptr.containers.init_default();
-        ptr.container_pool.init_default();
-        ptr.treenode_pool.init_default();
+        ptr.key_down = KeyState::default();
+        ptr.key_pressed = KeyState::default();
+        ptr.recording_start = 0;
+        ptr.diagnostics = config.diagnostics;
+        ptr.detached_layout = Layout::default();
 
         unsafe {
             ctx.assume_init()
         }
     }
 
+    /// Sets the number of physical pixels per logical pixel, i.e. `1.5` on a
+    /// screen scaled to 150%. The context lays out and draws in physical pixels,
+    /// so that text and borders stay sharp. The metrics of the [`Style`], text
+    /// sizes from the [`TextSizeHandler`] and the sizes given to the layout, i.e.
+    /// in [`Context::layout_row`] or [`Window::new`], are in logical pixels and
+    /// are multiplied by it. Input, [`Context::set_dock_area`], the `draw_*`
+    /// methods and the rects the context returns are in physical pixels.
+    ///
+    /// When it changes, the containers, their scroll offsets and the dock areas
+    /// are rescaled, so that windows keep their logical position and size.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        assert!(scale_factor > 0., "The scale factor must be positive.");
+
+        let ratio = scale_factor / self.scale_factor();
+        self.font_handler.scale_factor = scale_factor;
+
+        if ratio == 1. {
+            return;
+        }
+
+        let scale = |x: i32| (x as f32 * ratio).round() as i32;
+        let scale_rect = |r: Rect| {
+            let (x, y) = (scale(r.x), scale(r.y));
+
+            rect(x, y, scale(r.x + r.w) - x, scale(r.y + r.h) - y)
+        };
+
+        for container in self.containers.iter_mut() {
+            container.rect = scale_rect(container.rect);
+            container.scroll = vec2(scale(container.scroll.x), scale(container.scroll.y));
+        }
+
+        self.dock.area = scale_rect(self.dock.area);
+
+        for floating in &mut self.dock.floating {
+            floating.area = scale_rect(floating.area);
+        }
+    }
+
+    #[inline]
+    pub fn scale_factor(&self) -> f32 {
+        self.font_handler.scale_factor
+    }
+
+    /// Converts a length in logical pixels to whole physical pixels.
+    #[inline]
+    pub fn px(&self, logical: i32) -> i32 {
+        (logical as f32 * self.scale_factor()).round() as i32
+    }
+
+    /// Converts a length in physical pixels, i.e. the width of a rect the context
+    /// returned, to logical pixels, i.e. to pass it to [`Context::layout_row`].
+    #[inline]
+    pub fn logical(&self, physical: i32) -> i32 {
+        (physical as f32 / self.scale_factor()).round() as i32
+    }
+
+    /// Converts a rect in logical pixels to physical pixels. Its edges are
+    /// snapped to whole pixels, so rects that touch keep touching.
+    #[inline]
+    pub fn px_rect(&self, r: Rect) -> Rect {
+        let x = self.px(r.x);
+        let y = self.px(r.y);
+
+        rect(x, y, self.px(r.x + r.w) - x, self.px(r.y + r.h) - y)
+    }
+
+    /// Converts a rect in physical pixels to logical pixels, the inverse of
+    /// [`Context::px_rect`].
+    #[inline]
+    pub fn logical_rect(&self, r: Rect) -> Rect {
+        let x = self.logical(r.x);
+        let y = self.logical(r.y);
+
+        rect(x, y, self.logical(r.x + r.w) - x, self.logical(r.y + r.h) - y)
+    }
+
+    /// Converts the width of a line, i.e. a border or a caret, to physical pixels.
+    /// Lines are whole pixels wide and at least one pixel wide unless `logical`
+    /// is zero, so that they look even and don't vanish at fractional scales.
+    #[inline]
+    pub fn px_line(&self, logical: i32) -> i32 {
+        if logical > 0 {
+            cmp::max(self.px(logical), 1)
+        } else {
+            0
+        }
+    }
+
+    /// Converts a size given to the layout to physical pixels. Negative sizes
+    /// are offsets from the edge of the body where `-1` reaches the edge, so
+    /// only the offset from `-1` is scaled.
+    #[inline]
+    fn px_layout(&self, logical: i32) -> i32 {
+        if logical < 0 {
+            self.px(logical + 1) - 1
+        } else {
+            self.px(logical)
+        }
+    }
+
     pub fn begin(&mut self) {
         self.command_list.clear();
+        self.text_arena.clear();
+        self.point_arena.clear();
         self.root_list.clear();
+        self.errors.clear();
+        self.focus_list.clear();
         self.cursor_icon = None;
         self.scroll_target = None;
+        self.top_popup = None;
+        self.tooltip.pending = false;
+        self.close_menus = false;
         self.hover_root = self.next_hover_root.take();
         self.mouse_delta.x = self.mouse_pos.x - self.last_mouse_pos.x;
         self.mouse_delta.y = self.mouse_pos.y - self.last_mouse_pos.y;
@@ -341,10 +687,7 @@ impl Context {
     }
 
     pub fn end(&mut self) {
-        assert_eq!(self.container_stack.len(), 0, "You probably forgot to call end_window() or end_popup()");
-        assert_eq!(self.clip_stack.len(), 0);
-        assert_eq!(self.id_stack.len(), 0);
-        assert_eq!(self.layout_stack.len(), 0);
+        self.check_stacks();
 
         if let Some(index) = self.scroll_target {
             self.containers[index].scroll.x += self.scroll_delta.x;
@@ -356,6 +699,25 @@ impl Context {
         }
         self.updated_focus = false;
 
+        if self.key_pressed(Key::Tab) {
+            self.cycle_focus(self.key_down(Key::Shift));
+        }
+
+        // Escape closes the topmost popup unless a widget used it already.
+        if self.key_pressed(Key::Escape) {
+            if let Some(index) = self.top_popup {
+                self.containers[index].open = false;
+            }
+        }
+
+        self.update_dock();
+        self.update_tooltip();
+
+        // Discard the edit state once the text box loses focus.
+        if self.text_edit_id != self.focus_id {
+            self.text_edit_id = None;
+        }
+
         // Bring hover root to front if mouse was pressed
         if let Some(index) = self.next_hover_root {
             if self.mouse_any_pressed() {
@@ -371,7 +733,7 @@ impl Context {
             }
         }
 
-        self.key_pressed = ModKeyState::default();
+        self.key_pressed = KeyState::default();
         self.mouse_pressed = MouseState::default();
         self.scroll_delta = Vec2::ZERO;
         self.last_mouse_pos = self.mouse_pos;
@@ -390,12 +752,12 @@ impl Context {
             // If this is the first container then make the first command jump to it.
             // Otherwise set the previous container's tail to jump to this one.
             if i == 0 {
-                if let Some(cmd) = self.command_list.first_mut() {
-                    if let Command::Jump(dst) = cmd {
+                match self.command_list.first_mut() {
+                    Some(Command::Jump(dst)) => {
                         *dst = self.containers[cnt_idx].head.unwrap() + 1;
-                    } else {
-                        panic!("Widgets must be drawn inside of a window or a popup.")
-                    }
+                    },
+                    Some(_) => self.report(UiError::OutsideRoot),
+                    None => {}
                 }
             } else {
                 let prev_idx = self.root_list[i - 1];
@@ -424,38 +786,75 @@ impl Context {
                 }
             }
         }
+
+        self.update_damage();
     }
 
-    pub fn handle_commands(&mut self, handler: &mut impl CommandHandler) {
-        let mut i = 0;
+    /// The errors collected during the last frame if
+    /// [`ContextConfig::diagnostics`] is enabled.
+    #[inline]
+    pub fn errors(&self) -> &[UiError] {
+        &self.errors
+    }
 
-        while i < self.command_list.len() {
-            let cmd = unsafe {
-                self.command_list.read_at(i)  
-            };
+    /// Panics with the error unless diagnostics are enabled.
+    fn report(&mut self, error: UiError) {
+        assert!(self.diagnostics, "{}", error);
 
-            match cmd {
-                Command::Clip(rect) => handler.clip_cmd(rect),
-                Command::Rect { rect, color } => handler.rect_cmd(rect, color),
-                Command::Icon { id, rect, color } => handler.icon_cmd(id, rect, color),
-                Command::Text { font, pos, color, text } => handler.text_cmd(font, pos, color, text),
-                Command::Jump(dst) => {
-                    i = dst;
-
-                    continue;
+        if !self.errors.contains(&error) {
+            self.errors.push(error);
+        }
+    }
+
+    /// Reports the stacks that weren't emptied or ran out of space this frame.
+    /// Containers that weren't ended are ended here.
+    fn check_stacks(&mut self) {
+        if let Some(index) = self.current_container_index() {
+            let name = &self.containers[index].name;
+
+            self.report(UiError::Unbalanced {
+                stack: Stack::ContainerStack,
+                container: (!name.is_empty()).then(|| name.clone())
+            });
+
+            while let Some(index) = self.current_container_index() {
+                self.pop_clip_rect();
+
+                if self.containers[index].head.is_some() {
+                    self.end_root_container();
+                } else {
+                    self.pop_container();
                 }
             }
+        }
 
-            i += 1;
+        for (stack, len) in [
+            (Stack::ClipStack, self.clip_stack.len()),
+            (Stack::IdStack, self.id_stack.len()),
+            (Stack::LayoutStack, self.layout_stack.len())
+        ] {
+            if len > 0 {
+                self.report(UiError::Unbalanced { stack, container: None });
+            }
         }
 
-        // We must set the length to zero because we are doing a bitwise copy
-        // of each command which possibly includes strings. By doing this we
-        // are effectively transferring the ownership to the handler which will
-        // take care of freeing them. Otherwise, begin() will attempt to drop
-        // those strings the next frame which would result in a double-free.
-        unsafe {
-            self.command_list.set_len(0);
+        self.container_stack.clear();
+        self.clip_stack.clear();
+        self.id_stack.clear();
+        self.layout_stack.clear();
+
+        let overflows = [
+            self.command_list.take_overflow(),
+            self.root_list.take_overflow(),
+            self.container_stack.take_overflow(),
+            self.clip_stack.take_overflow(),
+            self.id_stack.take_overflow(),
+            self.layout_stack.take_overflow(),
+            self.focus_list.take_overflow()
+        ];
+
+        for stack in overflows.into_iter().flatten() {
+            self.report(UiError::StackOverflow(stack));
         }
     }
 
@@ -479,12 +878,50 @@ impl Context {
         self.hover_id.map_or(false, |x| x == id)
     }
 
+    /// The caret and selection of the text box with the given `id`
+    /// if it is currently being edited.
+    #[inline]
+    pub fn text_edit_state(&self, id: Id) -> Option<textbox::EditState> {
+        self.text_edit_id.filter(|x| *x == id).map(|_| self.text_edit)
+    }
+
     #[inline]
     pub fn set_focus(&mut self, id: Option<Id>) {
         self.focus_id = id;
+        self.key_focus = false;
         self.updated_focus = true;
     }
 
+    /// Moves the focus to the next focusable widget in the order they were updated
+    /// this frame, or the previous one if `reverse` is `true`. This is done for
+    /// Tab and Shift+Tab in [`Context::end`].
+    pub fn cycle_focus(&mut self, reverse: bool) {
+        let len = self.focus_list.len();
+
+        if len == 0 {
+            return;
+        }
+
+        let index = self.focus_id.and_then(|id| self.focus_list.iter().position(|x| *x == id));
+        let next = match (index, reverse) {
+            (Some(i), false) => (i + 1) % len,
+            (Some(i), true) => (i + len - 1) % len,
+            (None, false) => 0,
+            (None, true) => len - 1
+        };
+
+        self.set_focus(Some(self.focus_list[next]));
+        self.key_focus = true;
+    }
+
+    /// Returns `true` if the widget was focused with the keyboard and Space or Return was pressed.
+    #[inline]
+    pub fn key_activated(&self, id: Id) -> bool {
+        self.key_focus &&
+            self.is_focused(id) &&
+            (self.key_pressed(Key::Space) || self.key_pressed(Key::Return))
+    }
+
     #[inline]
     pub fn create_id(&mut self, item: &impl Hash) -> Id {
         let entropy = self.id_stack.last().copied().unwrap_or(Id::default());
@@ -558,26 +995,41 @@ impl Context {
     }
 
     #[inline]
-    pub fn key_down(&self, key: ModKey) -> bool {
+    pub fn key_down(&self, key: Key) -> bool {
         self.key_down.is_set(key)
     }
 
     #[inline]
-    pub fn key_pressed(&self, key: ModKey) -> bool {
+    pub fn key_pressed(&self, key: Key) -> bool {
         self.key_pressed.is_set(key)
     }
 
     #[inline]
-    pub fn key_up(&self, key: ModKey) -> bool {
+    pub fn key_up(&self, key: Key) -> bool {
         self.key_down.is_unset(key)
     }
 
+    /// Clears the pressed state of `key` for the rest of the frame so
+    /// that it isn't handled by anything else.
+    #[inline]
+    pub fn consume_key(&mut self, key: Key) {
+        self.key_pressed.unset(key);
+    }
+
+    /// Clears the pressed state of `btn` for the rest of the frame so
+    /// that it isn't handled by anything else.
+    #[inline]
+    pub fn consume_mouse(&mut self, btn: MouseButton) {
+        self.mouse_pressed.unset(btn);
+    }
+
+    /// Everything is clipped outside of a container.
     #[inline]
     pub fn check_clip(&self, rect: Rect) -> Clip {
         if let Some(last) = self.clip_stack.last() {
             last.clip(rect)
         } else {
-            Clip::None
+            Clip::All
         }
     }
 
@@ -597,9 +1049,10 @@ impl Context {
         self.command_list.push(Command::Clip(rect));
     }
 
+    /// The clip rect of the current container or an empty one outside of a container.
     #[inline]
     pub fn clip_rect(&self) -> Rect {
-        *self.clip_stack.last().unwrap()
+        self.clip_stack.last().copied().unwrap_or_default()
     }
 }
 
@@ -609,6 +1062,7 @@ impl Context {
 
 impl Context {
     pub fn draw_rect(&mut self, rect: Rect, color: Color) {
+        self.check_root();
         let rect = rect.intersect(self.clip_rect());
 
         if rect.w > 0 && rect.h > 0 {
@@ -616,6 +1070,7 @@ impl Context {
                 rect,
                 color
             });
+            self.add_bounds(rect);
         }
     }
 
@@ -627,29 +1082,32 @@ impl Context {
         self.draw_rect(rect(r.x + r.w - 1, r.y, 1, r.h), color);
     }
 
-    pub fn draw_text(&mut self, font: FontId, text: impl Into<String>, pos: Vec2, color: Color) {
-        let text: String = text.into();
-
+    pub fn draw_text(&mut self, font: FontId, text: impl AsRef<str>, pos: Vec2, color: Color) {
+        let text = text.as_ref();
         let rect = rect(
             pos.x,
             pos.y,
-            self.font_handler.text_width(font, &text),
+            self.font_handler.text_width(font, text),
             self.font_handler.text_height(font)
         );
 
-        let clip = self.check_clip(rect);
+        let clip = self.draw_clip(rect);
         match clip {
             Clip::None => {},
             Clip::All => { return; },
             Clip::Part => self.set_clip(self.clip_rect())
         }
 
+        let start = self.text_arena.len();
+        self.text_arena.push_str(text);
+
         self.command_list.push(Command::Text {
             font,
             pos,
             color,
-            text
+            text: ArenaRange { start, end: self.text_arena.len() }
         });
+        self.add_bounds(rect.intersect(self.clip_rect()));
 
         // Reset clipping if it was set.
         if !matches!(clip, Clip::None) {
@@ -658,7 +1116,7 @@ impl Context {
     }
 
     pub fn draw_icon(&mut self, id: Icon, rect: Rect, color: Color) {
-        let clip = self.check_clip(rect);
+        let clip = self.draw_clip(rect);
         match clip {
             Clip::None => {},
             Clip::All => { return; },
@@ -670,12 +1128,153 @@ impl Context {
             rect,
             color
         });
+        self.add_bounds(rect.intersect(self.clip_rect()));
+
+        // Reset clipping if it was set.
+        if !matches!(clip, Clip::None) {
+            self.set_clip(Rect::UNCLIPPED);
+        }
+    }
+
+    pub fn draw_image(&mut self, id: ImageId, rect: Rect, uv: UvRect, tint: Color) {
+        let clip = self.draw_clip(rect);
+        match clip {
+            Clip::None => {},
+            Clip::All => { return; },
+            Clip::Part => self.set_clip(self.clip_rect())
+        }
+
+        self.command_list.push(Command::Image {
+            id,
+            rect,
+            uv,
+            tint
+        });
+        self.add_bounds(rect.intersect(self.clip_rect()));
+
+        // Reset clipping if it was set.
+        if !matches!(clip, Clip::None) {
+            self.set_clip(Rect::UNCLIPPED);
+        }
+    }
+
+    /// Draws a line with square ends from `a` to `b`.
+    pub fn draw_line(&mut self, a: Vec2, b: Vec2, thickness: i32, color: Color) {
+        let bounds = Rect::bounding([a, b]).expand(thickness / 2 + 1);
+        self.push_clipped(bounds, Command::Line { a, b, thickness, color });
+    }
+
+    pub fn draw_rounded_rect(&mut self, rect: Rect, radius: i32, color: Color) {
+        self.push_clipped(rect, Command::RoundedRect { rect, radius, color });
+    }
+
+    pub fn draw_circle(&mut self, center: Vec2, radius: i32, color: Color) {
+        let bounds = rect(center.x - radius, center.y - radius, radius * 2, radius * 2);
+        self.push_clipped(bounds, Command::Circle { center, radius, color });
+    }
+
+    /// Draws the outline of a circle clockwise from `start` to `end`.
+    pub fn draw_arc(
+        &mut self,
+        center: Vec2,
+        radius: i32,
+        start: Angle,
+        end: Angle,
+        thickness: i32,
+        color: Color
+    ) {
+        let r = radius + thickness / 2 + 1;
+        let bounds = rect(center.x - r, center.y - r, r * 2, r * 2);
+
+        self.push_clipped(bounds, Command::Arc { center, radius, start, end, thickness, color });
+    }
+
+    pub fn draw_triangle(&mut self, points: [Vec2; 3], color: Color) {
+        self.push_clipped(Rect::bounding(points), Command::Triangle { points, color });
+    }
+
+    /// Fills the convex polygon with the corners `points`.
+    pub fn draw_polygon(&mut self, points: &[Vec2], color: Color) {
+        let bounds = Rect::bounding(points.iter().copied());
+
+        if points.len() < 3 || matches!(self.draw_clip(bounds), Clip::All) {
+            return;
+        }
+
+        let start = self.point_arena.len();
+        self.point_arena.extend_from_slice(points);
+
+        let points = ArenaRange { start, end: self.point_arena.len() };
+        self.push_clipped(bounds, Command::Polygon { points, color });
+    }
+
+    /// Approximates a blurred shadow with layers of translucent rounded rects.
+    fn draw_shadow(&mut self, rect: Rect, shadow: Shadow) {
+        let radius = self.px(self.style.corner_radius as i32);
+        let layers = cmp::max(self.px(shadow.blur as i32), 1);
+        let color = Color {
+            a: cmp::max(shadow.color.a as i32 / layers, 1) as u8,
+            ..shadow.color
+        };
+
+        let rect = Rect {
+            x: rect.x + self.px(shadow.offset.x),
+            y: rect.y + self.px(shadow.offset.y),
+            ..rect
+        };
+
+        for i in (0..layers).rev() {
+            self.draw_rounded_rect(rect.expand(i), radius + i, color);
+        }
+    }
+
+    /// Pushes a command that draws inside of `bounds` and
+    /// clips it if it's partly outside of the clip rect.
+    fn push_clipped(&mut self, bounds: Rect, cmd: Command) {
+        let clip = self.draw_clip(bounds);
+        match clip {
+            Clip::None => {},
+            Clip::All => { return; },
+            Clip::Part => self.set_clip(self.clip_rect())
+        }
+
+        self.command_list.push(cmd);
+        self.add_bounds(bounds.intersect(self.clip_rect()));
 
         // Reset clipping if it was set.
         if !matches!(clip, Clip::None) {
             self.set_clip(Rect::UNCLIPPED);
         }
     }
+
+    /// Like [`Context::check_clip`], but reports drawing outside of a root container.
+    #[inline]
+    fn draw_clip(&mut self, rect: Rect) -> Clip {
+        self.check_root();
+        self.check_clip(rect)
+    }
+
+    /// Commands can only be drawn inside of a window or a popup,
+    /// anything drawn outside of them is clipped away.
+    #[inline]
+    fn check_root(&mut self) {
+        if self.clip_stack.is_empty() {
+            self.report(UiError::OutsideRoot);
+        }
+    }
+
+    /// Grows the area drawn to by the current root container.
+    fn add_bounds(&mut self, rect: Rect) {
+        let root = self.container_stack.iter()
+            .rev()
+            .find(|x| self.containers[**x].head.is_some())
+            .copied();
+
+        if let Some(index) = root {
+            let bounds = &mut self.containers[index].bounds;
+            *bounds = bounds.union(rect);
+        }
+    }
 }
 
 //============================================================================
@@ -778,6 +1377,174 @@ impl Context {
     }
 }
 
+impl Container {
+    /// The name of the window or panel, used when reporting errors.
+    #[inline]
+    fn set_name(&mut self, name: &str) {
+        if self.name != name {
+            self.name = name.to_owned();
+        }
+    }
+}
+
+//============================================================================
+// Docking
+//============================================================================
+
+impl Context {
+    /// Sets the area that windows can be docked in, usually the whole screen.
+    /// Docking is disabled while the area is empty.
+    ///
+    /// Windows are docked by dropping them onto the edges of the area or onto
+    /// other docked windows. Dropping a window onto a floating window docks them
+    /// into a group of their own that takes up the area of the floating window.
+    #[inline]
+    pub fn set_dock_area(&mut self, rect: Rect) {
+        self.dock.area = rect;
+    }
+
+    #[inline]
+    pub fn dock_area(&self) -> Rect {
+        self.dock.area
+    }
+
+    #[inline]
+    pub fn dock_tree(&self) -> Option<&DockNode> {
+        self.dock.root.as_ref()
+    }
+
+    /// Replaces the layout of the docked windows, i.e. to restore one returned by [`Context::dock_tree`].
+    #[inline]
+    pub fn set_dock_tree(&mut self, tree: Option<DockNode>) {
+        self.dock.root = tree;
+        self.dock.drag = None;
+        self.dock.resizing = None;
+    }
+
+    /// Returns `true` if the window is docked in the dock area or onto another window.
+    #[inline]
+    pub fn is_docked(&self, title: &str) -> bool {
+        self.dock.contains(title)
+    }
+
+    /// Makes the window float again. Returns `false` if it wasn't docked.
+    #[inline]
+    pub fn undock_window(&mut self, title: &str) -> bool {
+        self.dock.remove(title)
+    }
+
+    /// Draws the tabs of a tab group in place of the title of its active window.
+    fn dock_tabs(&mut self, leaf: &DockLeaf, title_id: Id, title_rect: Rect, options: ContainerOptions) {
+        let font = self.style.font;
+        let padding = self.px(self.style.padding as i32);
+        let mut x = title_rect.x;
+
+        self.push_clip_rect(title_rect);
+
+        for (i, window) in leaf.windows.iter().enumerate() {
+            let w = self.font_handler.text_width(font, window) + padding * 2;
+            let r = rect(x, title_rect.y, w, title_rect.h);
+            x += w;
+
+            let id = if i == leaf.active {
+                title_id
+            } else {
+                self.create_id(&("!tab", i))
+            };
+
+            self.update_widget(id, r, WidgetInteraction::from(options));
+
+            if i == leaf.active {
+                self.draw_rect(r, self.style.colors[WidgetColor::WindowBackground]);
+            } else if self.is_hovered(id) {
+                self.draw_rect(r, self.style.colors[WidgetColor::ButtonHover]);
+            }
+
+            self.draw_widget_text(window.as_str(), r, WidgetColor::TitleText, options);
+
+            if i != leaf.active && self.is_focused(id) && self.mouse_pressed(MouseButton::Left) {
+                self.dock.set_active(leaf.group, leaf.index, i);
+            }
+        }
+
+        self.pop_clip_rect();
+
+        // Dragging the active tab pulls the window out of the dock.
+        if self.is_focused(title_id) &&
+            self.mouse_down(MouseButton::Left) &&
+            self.mouse_delta != Vec2::ZERO
+        {
+            self.dock.remove(&leaf.windows[leaf.active]);
+        }
+    }
+
+    /// Returns where the window of the container at `cnt_idx` would be docked if
+    /// it was dropped at the mouse. The topmost floating window or floating group
+    /// under the mouse is the target, otherwise the dock area is.
+    fn dock_target(&self, cnt_idx: usize) -> Option<(DockTarget, Rect)> {
+        let gap = self.px(self.style.spacing as i32);
+        let title_height = self.px(self.style.title_height as i32);
+        let pos = self.mouse_pos;
+
+        // Windows in the dock area are below all others.
+        let below = self.container_pool.iter()
+            .zip(self.containers.iter())
+            .enumerate()
+            .filter(|(i, (item, c))| {
+                *i != cnt_idx &&
+                    item.last_update + 1 >= self.frame &&
+                    c.window && c.open && !c.popup && c.zindex >= 0 &&
+                    !c.name.starts_with('!') &&
+                    c.rect.overlaps(pos)
+            })
+            .max_by_key(|(_, (_, c))| c.zindex);
+
+        match below {
+            Some((index, (_, c))) => match self.dock.leaf(&c.name, gap) {
+                Some(DockLeaf { group: DockGroup::Floating(group), .. }) => {
+                    self.dock.target_in(group, pos, gap, title_height)
+                },
+                _ => Some(Dock::target_on_window(index, c.rect, pos, gap, title_height))
+            },
+            None => self.dock.target_at(pos, gap, title_height)
+        }
+    }
+
+    /// Docks a dragged window once it's dropped and moves the dividers between docked windows.
+    fn update_dock(&mut self) {
+        let gap = self.px(self.style.spacing as i32);
+
+        if let Some(drag) = self.dock.drag.take() {
+            if self.mouse_down(MouseButton::Left) {
+                self.dock.drag = Some(drag);
+            } else if let Some((mut target, _)) = drag.target {
+                // Dropped onto a floating window, which starts a group with it.
+                if let DockGroup::Window(index) = target.group {
+                    let host = &self.containers[index];
+                    target.group = self.dock.float(host.name.clone(), host.rect);
+                }
+
+                self.dock.insert(drag.window, target);
+            }
+        }
+
+        if let Some((group, index)) = self.dock.resizing {
+            if self.mouse_down(MouseButton::Left) {
+                self.dock.resize(group, index, self.mouse_pos, gap);
+                self.cursor_icon = Some(CursorIcon::Drag);
+            } else {
+                self.dock.resizing = None;
+            }
+        } else if self.next_hover_root.is_none() && self.dock.splitter_at(self.mouse_pos, gap).is_some() {
+            self.cursor_icon = Some(CursorIcon::Drag);
+
+            if self.mouse_pressed(MouseButton::Left) {
+                self.dock.resizing = self.dock.splitter_at(self.mouse_pos, gap);
+            }
+        }
+    }
+}
+
 //============================================================================
 // Pool
 //============================================================================
@@ -790,11 +1557,22 @@ impl Context {
 
     #[inline]
     pub fn init_container_pool(&mut self, id: Id) -> Option<usize> {
-        self.container_pool.init(id, self.frame)
+        let index = self.container_pool.init(id, self.frame)?;
+
+        // Containers are stored at the same index as their pool items.
+        if index == self.containers.len() {
+            self.containers.push(Container::default());
+        }
+
+        Some(index)
     }
 }
 
-impl<const N: usize> ConstVec<PoolItem, N> {
+impl BoundedVec<PoolItem> {
+    /// Reuses a free item, adds a new one while there's space left
+    /// or replaces the one that was used least recently. Without a
+    /// capacity, items unused for [`POOL_EVICT_FRAMES`] are reused too,
+    /// so that the pool doesn't grow forever.
     fn init(&mut self, id: Id, frame: FrameIdx) -> Option<usize> {
         let mut index = None;
         let mut f = frame;
@@ -806,6 +1584,15 @@ impl<const N: usize> ConstVec<PoolItem, N> {
             }
         }
 
+        let is_stale = !self.is_bounded() && frame - f >= POOL_EVICT_FRAMES;
+        let is_free = index.is_some() && (f == 0 || is_stale);
+
+        if !is_free && self.free_space() > 0 {
+            self.push(PoolItem { id, last_update: frame });
+
+            return Some(self.len() - 1);
+        }
+
         if let Some(i) = index {
             self[i].id = id;
             self[i].last_update = frame;
@@ -829,44 +1616,87 @@ impl<const N: usize> ConstVec<PoolItem, N> {
 impl Context {
     #[inline]
     pub fn input_mouse_move(&mut self, pos: Vec2) {
+        self.record(|| InputEvent::MouseMove(pos));
         self.mouse_pos = pos;
     }
 
     #[inline]
     pub fn input_mouse_down(&mut self, pos: Vec2, btn: MouseButton) {
-        self.input_mouse_move(pos);
+        self.record(|| InputEvent::MouseDown(pos, btn));
+        self.mouse_pos = pos;
         self.mouse_down.set(btn);
         self.mouse_pressed.set(btn);
     }
 
     #[inline]
     pub fn input_mouse_up(&mut self, pos: Vec2, btn: MouseButton) {
-        self.input_mouse_move(pos);
+        self.record(|| InputEvent::MouseUp(pos, btn));
+        self.mouse_pos = pos;
         self.mouse_down.unset(btn);
     }
 
     #[inline]
     pub fn input_scroll(&mut self, delta: Vec2) {
+        self.record(|| InputEvent::Scroll(delta));
         self.scroll_delta.x += delta.x;
         self.scroll_delta.y += delta.y;
     }
 
     #[inline]
-    pub fn input_key_down(&mut self, key: ModKey) {
+    pub fn input_key_down(&mut self, key: Key) {
+        self.record(|| InputEvent::KeyDown(key));
         self.key_down.set(key);
         self.key_pressed.set(key);
     }
 
     #[inline]
-    pub fn input_key_up(&mut self, key: ModKey) {
+    pub fn input_key_up(&mut self, key: Key) {
+        self.record(|| InputEvent::KeyUp(key));
         self.key_down.unset(key);
     }
 
     /// The maximum size of the backing store is [`MAX_TEXT_STORE`].
     /// Returns the number of bytes written.
     #[inline]
-    pub fn input_text(&mut self, text: &str) -> usize {
-        self.text_input.push_str(text)
+    pub fn input_text(&mut self, text: &str) -> usize {
+        self.record(|| InputEvent::Text(text.into()));
+        self.text_input.push_str(text)
+    }
+
+    /// Start capturing all input events along with the frame they were received in.
+    /// Any recording that is already in progress is discarded.
+    #[inline]
+    pub fn start_recording(&mut self) {
+        self.recording = Some(InputRecording::default());
+        self.recording_start = self.frame;
+    }
+
+    #[inline]
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        self.recording.take()
+    }
+
+    #[inline]
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Replaces the clipboard used by text widgets. The default is a [`MemoryClipboard`].
+    #[inline]
+    pub fn set_clipboard(&mut self, clipboard: impl Clipboard + 'static) {
+        self.clipboard = Box::new(clipboard);
+    }
+
+    #[inline]
+    pub fn clipboard(&mut self) -> &mut dyn Clipboard {
+        &mut *self.clipboard
+    }
+
+    #[inline]
+    fn record(&mut self, event: impl FnOnce() -> InputEvent) {
+        if let Some(recording) = &mut self.recording {
+            recording.push(self.frame - self.recording_start, event());
+        }
     }
 }
 
@@ -882,8 +1712,11 @@ impl Context {
     }
 
     pub fn layout_end_column(&mut self) {
-        let b = self.layout_stack.pop().unwrap();
-        let a = self.layout_stack.last_mut().unwrap();
+        let Some(b) = self.layout_stack.pop() else {
+            return;
+        };
+
+        let a = self.layout_mut();
         
         a.pos.x = cmp::max(a.pos.x, b.pos.x + b.body.x - a.body.x);
         a.next_row = cmp::max(a.next_row, b.next_row + b.body.y - a.body.y);
@@ -891,26 +1724,46 @@ impl Context {
         a.max.y = cmp::max(a.max.y, b.max.y);
     }
 
-    #[inline]
+    /// `widths` and `height` are in logical pixels, see [`Context::set_scale_factor`].
+    /// Convert sizes taken from the rects of the context, like the width of the
+    /// body of the current container, with [`Context::logical`] first.
     pub fn layout_row(&mut self, widths: &[i32], height: i32) {
-        let layout = self.layout_stack.last_mut().unwrap();
-        layout.row(widths, height);
+        let mut scaled = [0; MAX_WIDTHS];
+        assert!(widths.len() <= MAX_WIDTHS);
+
+        for (scaled, width) in scaled.iter_mut().zip(widths) {
+            *scaled = self.px_layout(*width);
+        }
+
+        let height = self.px_layout(height);
+        let layout = self.layout_mut();
+        layout.row(&scaled[..widths.len()], height);
     }
 
+    /// `height` is in logical pixels, see [`Context::set_scale_factor`].
     #[inline]
     pub fn layout_row_items(&mut self, items: usize, height: i32) {
-        let layout = self.layout_stack.last_mut().unwrap();
+        let height = self.px_layout(height);
+        let layout = self.layout_mut();
         layout.row_items(items, height);
     }
 
+    /// `rect` is in physical pixels, i.e. from [`Context::layout_next`].
     #[inline]
     pub fn layout_set_next(&mut self, rect: Rect, ty: LayoutType) {
-        let layout = self.layout_stack.last_mut().unwrap();
+        let layout = self.layout_mut();
         layout.set_next(rect, ty);
     }
 
     pub fn layout_next(&mut self) -> Rect {
-        let layout = self.layout_stack.last_mut().unwrap();
+        let padding = self.px(self.style.padding as i32);
+        let default_size = vec2(
+            self.px(self.style.size.x) + padding * 2,
+            self.px(self.style.size.y) + padding * 2
+        );
+        let spacing = self.px(self.style.spacing as i32);
+
+        let layout = self.layout_mut();
 
         let mut result = if layout.next_type.is_some() {
             let ty = layout.next_type.take().unwrap();
@@ -939,14 +1792,12 @@ impl Context {
                 layout.size.y
             );
 
-            let style = &self.style;
-
             if result.w == 0 {
-                result.w = style.size.x + style.padding as i32 * 2;
+                result.w = default_size.x;
             }
 
             if result.h == 0 {
-                result.h = style.size.y + style.padding as i32 * 2;
+                result.h = default_size.y;
             }
 
             if result.w < 0 {
@@ -962,7 +1813,6 @@ impl Context {
             result
         };
 
-        let spacing = (self.style.spacing) as i32;
         layout.pos.x += result.w + spacing;
         layout.next_row = cmp::max(layout.next_row, result.y + result.h + spacing);
 
@@ -977,6 +1827,19 @@ impl Context {
         result
     }
 
+    /// The layout of the current container. Widgets outside of a container
+    /// are laid out in a detached layout that is never drawn.
+    fn layout_mut(&mut self) -> &mut Layout {
+        if self.layout_stack.is_empty() {
+            self.report(UiError::OutsideRoot);
+            self.detached_layout = Layout::default();
+
+            return &mut self.detached_layout;
+        }
+
+        self.layout_stack.last_mut().unwrap()
+    }
+
     fn push_layout(&mut self, body: Rect, scroll: Vec2) {
         let mut layout = Layout {
             body: rect(body.x - scroll.x, body.y - scroll.y, body.w, body.h),
@@ -1067,33 +1930,48 @@ impl Context {
     }
 
     /// Returns the [`Rect`] of the measured text with clipping **taken into account**.
+    #[inline]
     pub fn draw_widget_text(
         &mut self,
         text: impl Into<String>,
         rect: Rect,
         color_id: WidgetColor,
         options: ContainerOptions
+    ) -> Rect {
+        self.draw_widget_text_with(self.style.font, text, rect, color_id, options)
+    }
+
+    /// Same as [`Context::draw_widget_text`] but with `font` instead of the style's font.
+    pub fn draw_widget_text_with(
+        &mut self,
+        font: FontId,
+        text: impl Into<String>,
+        rect: Rect,
+        color_id: WidgetColor,
+        options: ContainerOptions
     ) -> Rect {
         let text: String = text.into();
 
-        let font = self.style.font;
         let width = self.font_handler.text_width(font, &text);
         let height = self.font_handler.text_height(font);
 
         self.push_clip_rect(rect);
 
+        // Right-to-left text starts at the right unless it's aligned to the right.
+        let align_right = options.is_set(ContainerOption::AlignRight) != text::is_rtl(&text);
+
         let mut pos = vec2(0, rect.y + (rect.h - height) / 2);
         pos.x = if options.is_set(ContainerOption::AlignCenter) {
             rect.x + (rect.w - width) / 2
-        } else if options.is_set(ContainerOption::AlignRight) {
-            rect.x + rect.w - width - self.style.padding as i32
+        } else if align_right {
+            rect.x + rect.w - width - self.px(self.style.padding as i32)
         } else {
-            rect.x + self.style.padding as i32
+            rect.x + self.px(self.style.padding as i32)
         };
 
         self.draw_text(
             font,
-            text,
+            &text,
             pos,
             self.style.colors[color_id]
         );
@@ -1125,6 +2003,10 @@ impl Context {
             return;
         }
 
+        if interact.focusable && self.focus_list.free_space() > 0 {
+            self.focus_list.push(id);
+        }
+
         let mouse_over = self.is_mouse_over(rect);
 
         if mouse_over && !self.mouse_any_down() {
@@ -1136,7 +2018,11 @@ impl Context {
                 self.set_focus(None);
             }
 
-            if !self.mouse_any_down() && interact.options.is_unset(ContainerOption::HoldFocus) {
+            // Widgets focused with the keyboard keep their focus until it's moved away.
+            if !self.mouse_any_down() &&
+                interact.options.is_unset(ContainerOption::HoldFocus) &&
+                !self.key_focus
+            {
                 self.set_focus(None);
             }
         }
@@ -1149,6 +2035,14 @@ impl Context {
             }
         }
 
+        if interact.focusable && self.key_focus && self.is_focused(id) {
+            let offset = self.px(2);
+
+            for i in 0..self.px_line(1) {
+                self.draw_box(rect.expand(offset + i), self.style.colors[WidgetColor::FocusRing]);
+            }
+        }
+
         if interact.cursor.is_some() {
             // We don't want to change the cursor if another widget
             // wants to retain its cursor while focused.
@@ -1169,34 +2063,13 @@ impl Context {
         let font = self.style.font;
 
         let height = self.font_handler.text_height(font);
-        self.layout_row(&[-1], height);
+        self.layout_mut().row(&[-1], height);
 
         let mut slice = &text[..];
 
         while slice.len() > 0 {
-            let mut w = 0;
-            let mut start = 0;
-            let mut end = slice.len();
             let rect = self.layout_next();
-
-            for (i, c) in slice.char_indices().filter(|x| x.1 == ' ' || x.1 == '\n') {
-                let word = &slice[start..i];
-                w += self.font_handler.text_width(font, word);
-
-                if w > rect.w && start != 0 {
-                    end = start;
-                    break;
-                }
-
-                w += self.font_handler.text_width(font, &slice[i..i+1]);
-
-                if c == '\n' {
-                    end = i + 1;
-                    break;
-                }
-
-                start = i + 1;
-            }
+            let end = self.wrap_line(font, slice, rect.w);
 
             self.draw_text(
                 font,
@@ -1211,6 +2084,61 @@ impl Context {
         self.layout_end_column();
     }
 
+    /// Returns the length of the first line of `text` when it's broken
+    /// at spaces and line breaks to fit into `width` pixels. The line
+    /// includes the space or line break it was broken at. Words that are
+    /// too long for a line by themselves, i.e. text without spaces in
+    /// Chinese, are broken between grapheme clusters instead.
+    fn wrap_line(&self, font: FontId, text: &str, width: i32) -> usize {
+        let mut w = 0;
+        let mut start = 0;
+
+        let breaks = text.char_indices()
+            .filter(|x| x.1 == ' ' || x.1 == '\n')
+            .map(Some)
+            .chain([None]);
+
+        for brk in breaks {
+            let end = brk.map_or(text.len(), |x| x.0);
+            w += self.font_handler.text_width(font, &text[start..end]);
+
+            if w > width {
+                return if start != 0 {
+                    start
+                } else {
+                    self.fit_graphemes(font, &text[..end], width)
+                };
+            }
+
+            match brk {
+                Some((i, c)) => {
+                    w += self.font_handler.text_width(font, &text[i..i+1]);
+
+                    if c == '\n' {
+                        return i + 1;
+                    }
+
+                    start = i + 1;
+                },
+                None => break
+            }
+        }
+
+        text.len()
+    }
+
+    /// The length of the longest start of `text` that ends between grapheme
+    /// clusters and fits into `width` pixels, but at least one grapheme cluster.
+    fn fit_graphemes(&self, font: FontId, text: &str, width: i32) -> usize {
+        let mut boundaries = text::grapheme_boundaries(text).skip(1);
+        let first = boundaries.next().unwrap_or(text.len());
+
+        boundaries
+            .take_while(|&i| self.font_handler.text_width(font, &text[..i]) <= width)
+            .last()
+            .unwrap_or(first)
+    }
+
     /// Shorthand for `Label::new(text)`.
     #[inline]
     pub fn label(&mut self, text: impl Into<String>) {
@@ -1229,6 +2157,31 @@ impl Context {
         Button::new(text).draw(self).submit
     }
 
+    /// Shorthand for `MenuItem::new(text)`.
+    #[inline]
+    pub fn menu_item(&mut self, text: impl Into<String>) -> bool {
+        MenuItem::new(text).draw(self).submit
+    }
+
+    /// Shorthand for `MenuItem::new(text).checkable(checked)`.
+    /// Returns `true` if it was toggled.
+    #[inline]
+    pub fn menu_check(&mut self, text: impl Into<String>, checked: &mut bool) -> bool {
+        MenuItem::new(text).checkable(checked).draw(self).change
+    }
+
+    /// Shorthand for `Image::new(id)`.
+    #[inline]
+    pub fn image(&mut self, id: ImageId) {
+        Image::new(id).draw(self);
+    }
+
+    /// Shorthand for `ImageButton::new(id)`.
+    #[inline]
+    pub fn image_button(&mut self, id: ImageId) -> bool {
+        ImageButton::new(id).draw(self).submit
+    }
+
     /// Shorthand for `Checkbox::new(label, &mut checked)`.
     /// 
     /// Returns `true` if the checked state changed.
@@ -1243,6 +2196,12 @@ impl Context {
         TextBox::new(buf).draw(self)
     }
 
+    /// Shorthand for `TextArea::new(buf)`
+    #[inline]
+    pub fn text_area(&mut self, buf: &mut impl TextBuf) -> Response {
+        TextArea::new(buf).draw(self)
+    }
+
     /// Shorthand for `Slider::new(&mut value, range)`.
     /// 
     /// Returns `true` if the value changed.
@@ -1311,35 +2270,83 @@ impl Context {
         }
 
         self.id_stack.push(id);
+        self.containers[cnt_idx].set_name(&title);
+        self.containers[cnt_idx].popup = options.is_set(ContainerOption::Popup);
+        self.containers[cnt_idx].window = true;
 
         if self.containers[cnt_idx].rect.w == 0 {
             self.containers[cnt_idx].rect = rect;
         }
 
+        let mut options = options;
+        let docked = self.dock.leaf(&title, self.px(self.style.spacing as i32));
+
+        if let Some(leaf) = &docked {
+            // Only the active tab of a group is shown.
+            if leaf.windows[leaf.active] != title {
+                self.id_stack.pop();
+
+                return false;
+            }
+
+            self.containers[cnt_idx].rect = leaf.rect;
+
+            // Floating groups stay on top of the dock area.
+            if leaf.group == DockGroup::Area {
+                self.containers[cnt_idx].zindex = -1;
+            }
+
+            options.set(ContainerOption::NoResize);
+            options.unset(ContainerOption::AutoSize);
+        } else if self.containers[cnt_idx].docked {
+            self.bring_to_front(cnt_idx);
+        }
+
+        self.containers[cnt_idx].docked = docked.is_some();
+
+        if options.is_set(ContainerOption::Popup) {
+            self.containers[cnt_idx].parent = self.current_root_index().map(|x| self.container_pool[x].id);
+        }
+
         self.begin_root_container(cnt_idx);
 
         rect = self.containers[cnt_idx].rect;
         let mut body = rect;
 
         if options.is_unset(ContainerOption::NoFrame) {
+            if let (Some(shadow), None) = (self.style.shadow, &docked) {
+                self.draw_shadow(rect, shadow);
+            }
+
             (self.draw_frame)(self, rect, WidgetColor::WindowBackground);
         }
 
         // Title bar
         if options.is_unset(ContainerOption::NoTitle) {
             let mut title_rect = rect;
-            title_rect.h = self.style.title_height as i32;
+            title_rect.h = self.px(self.style.title_height as i32);
 
             (self.draw_frame)(self, title_rect, WidgetColor::TitleBackground);
 
             // Title text
             let id = self.create_id(&"!title");
-            self.update_widget(id, title_rect, WidgetInteraction::from(options));
-            self.draw_widget_text(title, title_rect, WidgetColor::TitleText, options);
+
+            if let Some(leaf) = &docked {
+                self.dock_tabs(leaf, id, title_rect, options);
+            } else {
+                self.update_widget(id, title_rect, WidgetInteraction::from(options));
+                self.draw_widget_text(title.as_str(), title_rect, WidgetColor::TitleText, options);
+            }
 
             if self.is_focused(id) && self.mouse_down.is_set(MouseButton::Left) {
                 self.containers[cnt_idx].rect.x += self.mouse_delta.x;
                 self.containers[cnt_idx].rect.y += self.mouse_delta.y;
+
+                if docked.is_none() && options.is_unset(ContainerOption::Popup) {
+                    let target = self.dock_target(cnt_idx);
+
+                    self.dock.drag = Some(DockDrag { window: title.clone(), container: cnt_idx, target });
+                }
             }
 
             body.y += title_rect.h;
@@ -1366,12 +2373,13 @@ impl Context {
 
                 if self.mouse_pressed.is_set(MouseButton::Left) && self.is_focused(id) {
                     self.containers[cnt_idx].open = false;
+                    self.dock.remove(&title);
                 }
             }
         }
 
         if options.is_unset(ContainerOption::NoResize) {
-            let sz = self.style.footer_height as i32;
+            let sz = self.px(self.style.footer_height as i32);
             let id = self.create_id(&"!resize");
             let r = Rect {
                 x: rect.x + rect.w - sz,
@@ -1392,8 +2400,8 @@ impl Context {
             if self.is_focused(id) && self.mouse_down.is_set(MouseButton::Left) {
                 let cnt_rect = self.containers[cnt_idx].rect;
 
-                self.containers[cnt_idx].rect.w = cmp::max(96, cnt_rect.w + self.mouse_delta.x);
-                self.containers[cnt_idx].rect.h = cmp::max(64, cnt_rect.h + self.mouse_delta.y);
+                self.containers[cnt_idx].rect.w = cmp::max(self.px(96), cnt_rect.w + self.mouse_delta.x);
+                self.containers[cnt_idx].rect.h = cmp::max(self.px(64), cnt_rect.h + self.mouse_delta.y);
             }
 
             body.h -= sz;
@@ -1402,7 +2410,7 @@ impl Context {
         self.push_container_body(cnt_idx, body, options);
 
         if options.is_set(ContainerOption::AutoSize) {
-            let r = self.layout_stack.last().unwrap().body;
+            let r = self.layout_mut().body;
             let cnt_rect = self.containers[cnt_idx].rect;
             let content_size = self.containers[cnt_idx].content_size;
 
@@ -1410,14 +2418,21 @@ impl Context {
             self.containers[cnt_idx].rect.h = content_size.y + (cnt_rect.h - r.h);
         }
 
-        // Close if this is a popup window and elsewhere was clicked.
+        // Close if this is a popup window and elsewhere was clicked,
+        // except in a popup that was opened from this one.
         if options.is_set(ContainerOption::Popup) &&
             self.mouse_any_pressed() &&
-            self.hover_root.map_or(false, |x| x != cnt_idx)
+            self.hover_root.is_some_and(|x| !self.opened_from(x, cnt_idx))
         {
             self.containers[cnt_idx].open = false;
         }
 
+        if options.is_set(ContainerOption::Popup) &&
+            self.top_popup.is_none_or(|x| self.containers[cnt_idx].zindex > self.containers[x].zindex)
+        {
+            self.top_popup = Some(cnt_idx);
+        }
+
         self.push_clip_rect(self.containers[cnt_idx].body);
 
         return true;
@@ -1426,6 +2441,14 @@ impl Context {
     #[inline]
     pub fn end_window(&mut self) {
         self.pop_clip_rect();
+
+        // Show where the window would be docked on top of everything else in it.
+        if let Some(DockDrag { container, target: Some((_, r)), .. }) = self.dock.drag {
+            if self.current_container_index() == Some(container) {
+                self.draw_rect(r, self.style.colors[WidgetColor::DockPreview]);
+            }
+        }
+
         self.end_root_container();
     }
 
@@ -1448,6 +2471,7 @@ impl Context {
 
         let rect = self.layout_next();
         self.containers[cnt_idx].rect = rect;
+        self.containers[cnt_idx].set_name(&name);
 
         if options.is_unset(ContainerOption::NoFrame) {
             (self.draw_frame)(self, rect, WidgetColor::PanelBackground);
@@ -1519,7 +2543,7 @@ impl Context {
             self.style.colors[WidgetColor::Text]
         );
 
-        let padding = self.style.padding as i32; 
+        let padding = self.px(self.style.padding as i32);
         r.x += r.h - padding;
         r.w -= r.h - padding;
 
@@ -1537,8 +2561,8 @@ impl Context {
         cnt_idx: usize,
         body: &mut Rect,
     ) {
-        let scrollbar_size = self.style.scrollbar_size as i32;
-        let padding = self.style.padding as i32;
+        let scrollbar_size = self.px(self.style.scrollbar_size as i32);
+        let padding = self.px(self.style.padding as i32);
 
         let mut content_size = self.containers[cnt_idx].content_size;
         content_size.x += padding * 2;
@@ -1573,7 +2597,7 @@ impl Context {
         }
 
         self.push_layout(
-            body.expand(-(self.style.padding as i32)),
+            body.expand(-self.px(self.style.padding as i32)),
             self.containers[cnt_idx].scroll
         );
         self.containers[cnt_idx].body = body;
@@ -1584,9 +2608,10 @@ impl Context {
 
         // Push container to roots list and push head command.
         self.root_list.push(cnt_idx);
-        self.command_list.push(Command::Jump(0));
+        self.command_list.push_required(Command::Jump(0));
 
         self.containers[cnt_idx].head = Some(self.command_list.len() - 1);
+        self.containers[cnt_idx].bounds = Rect::default();
 
         // Set as hover root if the mouse is overlapping this container
         // and it has a higher zindex than the current hover root.
@@ -1609,7 +2634,7 @@ impl Context {
         // Push tail 'goto' jump command and set head 'skip' command.
         // The final steps on initing these are done in end()
         let index = self.current_container_index().unwrap();
-        self.command_list.push(Command::Jump(0));
+        self.command_list.push_required(Command::Jump(0));
 
         self.containers[index].tail = Some(self.command_list.len() - 1);
 
@@ -1627,6 +2652,34 @@ impl Context {
         self.pop_container();
     }
 
+    /// The root container that the current container is in.
+    fn current_root_index(&self) -> Option<usize> {
+        self.container_stack.iter()
+            .rev()
+            .find(|index| self.containers[**index].head.is_some())
+            .copied()
+    }
+
+    /// Returns `true` if the container at `index` is the one at `root` or
+    /// a popup that was opened from it, directly or through other popups.
+    fn opened_from(&self, index: usize, root: usize) -> bool {
+        iter::successors(Some(index), |x| {
+            self.containers[*x].parent.and_then(|id| self.container_pool.find_by_id(id))
+        })
+            .take(self.containers.len())
+            .any(|x| x == root)
+    }
+
+    /// Closes the popups that were opened from the container at `index`,
+    /// so that they don't show up again when it's opened.
+    fn close_popups_from(&mut self, index: usize) {
+        for i in 0..self.containers.len() {
+            if i != index && self.opened_from(i, index) {
+                self.containers[i].open = false;
+            }
+        }
+    }
+
     fn in_hover_root(&self) -> bool {
         if self.hover_root.is_none() {
             return false;
@@ -1666,7 +2719,7 @@ macro_rules! scrollbar {
 
                 let mut base = *body;
                 base.$x = body.$x + body.$w;
-                base.$w = self.style.scrollbar_size as i32;
+                base.$w = self.px(self.style.scrollbar_size as i32);
 
                 self.update_widget(id, base, WidgetInteraction::default());
 
@@ -1679,7 +2732,7 @@ macro_rules! scrollbar {
                 (self.draw_frame)(self, base, WidgetColor::ScrollBase);
 
                 let mut thumb = base;
-                thumb.$h = cmp::max(self.style.thumb_size as i32, base.$h * body.$h / content_size.$y);
+                thumb.$h = cmp::max(self.px(self.style.thumb_size as i32), base.$h * body.$h / content_size.$y);
                 thumb.$y += self.containers[cnt_idx].scroll.$y * (base.$h - thumb.$h) / maxscroll;
 
                 (self.draw_frame)(self, thumb, WidgetColor::ScrollThumb);
@@ -1741,6 +2794,14 @@ impl WidgetInteraction {
 
         self
     }
+
+    /// The widget can be focused with Tab and Shift+Tab.
+    #[inline]
+    pub fn focusable(mut self) -> Self {
+        self.focusable = true;
+
+        self
+    }
 }
 
 impl From<ContainerOptions> for WidgetInteraction {
@@ -1748,7 +2809,568 @@ impl From<ContainerOptions> for WidgetInteraction {
         Self {
             options,
             cursor: None,
-            retain_cursor_focus: false
+            retain_cursor_focus: false,
+            focusable: false
+        }
+    }
+}
+
+impl TextSizeHandler for ScaledTextSizeHandler {
+    #[inline]
+    fn text_width(&self, id: FontId, text: &str) -> i32 {
+        (self.handler.text_width(id, text) as f32 * self.scale_factor).round() as i32
+    }
+
+    #[inline]
+    fn text_height(&self, id: FontId) -> i32 {
+        (self.handler.text_height(id) as f32 * self.scale_factor).round() as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{Handler, Counter};
+
+    #[derive(Default)]
+    struct State {
+        ids: Vec<Id>,
+        clicked: bool,
+        checked: bool,
+        text: ConstStr<16>
+    }
+
+    fn frame(ctx: &mut Context, state: &mut State, keys: &[Key]) {
+        for key in keys {
+            ctx.input_key_down(*key);
+        }
+
+        state.ids.clear();
+
+        ctx.begin();
+        Window::new("Focus", rect(0, 0, 200, 200)).show(ctx, |ctx| {
+            if ctx.button("Button") {
+                state.clicked = true;
+            }
+
+            state.ids.push(ctx.last_id().unwrap());
+            ctx.label("Not focusable");
+            ctx.checkbox("Checkbox", &mut state.checked);
+            state.ids.push(ctx.last_id().unwrap());
+            ctx.textbox(&mut state.text);
+            state.ids.push(ctx.last_id().unwrap());
+        });
+        ctx.end();
+
+        for key in keys {
+            ctx.input_key_up(*key);
+        }
+    }
+
+    #[test]
+    fn tab_focus() {
+        let mut ctx = Context::new(Handler);
+        let mut state = State::default();
+
+        frame(&mut ctx, &mut state, &[]);
+        frame(&mut ctx, &mut state, &[Key::Tab]);
+        assert!(ctx.is_focused(state.ids[0]));
+
+        // Keyboard focus is kept without holding the mouse button.
+        frame(&mut ctx, &mut state, &[]);
+        assert!(ctx.is_focused(state.ids[0]));
+
+        frame(&mut ctx, &mut state, &[Key::Space]);
+        assert!(state.clicked);
+
+        frame(&mut ctx, &mut state, &[Key::Tab]);
+        frame(&mut ctx, &mut state, &[Key::Return]);
+        assert!(ctx.is_focused(state.ids[1]));
+        assert!(state.checked);
+
+        frame(&mut ctx, &mut state, &[Key::Tab]);
+        assert!(ctx.is_focused(state.ids[2]));
+
+        frame(&mut ctx, &mut state, &[Key::Tab]);
+        assert!(ctx.is_focused(state.ids[0]));
+
+        frame(&mut ctx, &mut state, &[Key::Shift, Key::Tab]);
+        assert!(ctx.is_focused(state.ids[2]));
+
+        // Clicking somewhere else removes the focus.
+        ctx.input_mouse_down(vec2(150, 170), MouseButton::Left);
+        frame(&mut ctx, &mut state, &[]);
+        assert!(!state.ids.iter().any(|id| ctx.is_focused(*id)));
+    }
+
+    #[test]
+    fn escape() {
+        let mut ctx = Context::new(Handler);
+        let mut value = 5.0;
+        let mut shown = [false; 2];
+
+        let mut frame = |ctx: &mut Context, keys: &[Key], open: bool| {
+            for key in keys {
+                ctx.input_key_down(*key);
+            }
+
+            ctx.begin();
+            Window::new("Escape", rect(0, 0, 200, 200)).show(ctx, |ctx| {
+                ctx.layout_row(&[-1], 0);
+                ctx.slider(&mut value, 0.0..10.0);
+
+                for (i, name) in ["A", "B"].into_iter().enumerate() {
+                    let popup = Popup::new(name);
+
+                    if open {
+                        popup.open(ctx);
+                    }
+
+                    shown[i] = false;
+                    popup.show(ctx, |_| shown[i] = true);
+                }
+            });
+            ctx.end();
+
+            for key in keys {
+                ctx.input_key_up(*key);
+            }
+
+            (value, shown)
+        };
+
+        frame(&mut ctx, &[], true);
+        assert_eq!(frame(&mut ctx, &[Key::Escape], false).1, [true, true]);
+        assert_eq!(frame(&mut ctx, &[], false).1, [true, false]);
+        frame(&mut ctx, &[Key::Escape], false);
+        assert_eq!(frame(&mut ctx, &[], false).1, [false, false]);
+
+        // Shift-click the slider to edit its value as text.
+        ctx.input_mouse_move(vec2(100, 40));
+        frame(&mut ctx, &[], false);
+        frame(&mut ctx, &[], false);
+        ctx.input_key_down(Key::Shift);
+        ctx.input_mouse_down(vec2(100, 40), MouseButton::Left);
+        frame(&mut ctx, &[], false);
+        ctx.input_mouse_up(vec2(100, 40), MouseButton::Left);
+        ctx.input_key_up(Key::Shift);
+        assert!(ctx.number_edit_id.is_some());
+
+        ctx.input_text("9");
+        frame(&mut ctx, &[], false);
+        assert_eq!(frame(&mut ctx, &[Key::Escape], false).0, 5.0);
+        assert!(ctx.number_edit_id.is_none() && ctx.focus_id.is_none());
+    }
+
+    #[test]
+    fn docking() {
+        let mut ctx = Context::new(Handler);
+        ctx.set_dock_area(rect(0, 0, 400, 300));
+        ctx.set_dock_tree(Some(DockNode::tabs(["A"])));
+
+        let frame = |ctx: &mut Context| {
+            let mut rects = Vec::new();
+
+            ctx.begin();
+
+            for (title, r) in [("A", rect(10, 10, 100, 100)), ("B", rect(50, 50, 100, 100))] {
+                Window::new(title, r).show(ctx, |ctx| rects.push(ctx.current_container().rect));
+            }
+
+            ctx.end();
+
+            rects
+        };
+
+        let drag = |ctx: &mut Context, from: Vec2, to: Vec2| {
+            ctx.input_mouse_move(from);
+            frame(ctx);
+            frame(ctx);
+            ctx.input_mouse_down(from, MouseButton::Left);
+            frame(ctx);
+            ctx.input_mouse_move(to);
+            let rects = frame(ctx);
+            ctx.input_mouse_up(to, MouseButton::Left);
+            frame(ctx);
+
+            rects
+        };
+
+        assert_eq!(frame(&mut ctx), [rect(0, 0, 400, 300), rect(50, 50, 100, 100)]);
+
+        // Drop B on the right edge of the screen.
+        drag(&mut ctx, vec2(60, 60), vec2(390, 150));
+        assert_eq!(ctx.dock_tree(), Some(&DockNode::split(
+            DockAxis::Horizontal,
+            0.5,
+            DockNode::tabs(["A"]),
+            DockNode::tabs(["B"])
+        )));
+        assert_eq!(frame(&mut ctx), [rect(0, 0, 198, 300), rect(202, 0, 198, 300)]);
+
+        // Drag the divider between them.
+        drag(&mut ctx, vec2(199, 150), vec2(100, 150));
+        assert_eq!(frame(&mut ctx)[0].w, 100);
+
+        // Pull B back out by its tab.
+        let rects = drag(&mut ctx, vec2(110, 10), vec2(120, 20));
+        assert_eq!(ctx.dock_tree(), Some(&DockNode::tabs(["A"])));
+        assert!(ctx.is_docked("A") && !ctx.is_docked("B"));
+        assert_eq!(rects[1].x, 114);
+    }
+
+    #[test]
+    fn dock_floating() {
+        let mut ctx = Context::new(Handler);
+        ctx.set_dock_area(rect(0, 0, 400, 300));
+
+        let frame = |ctx: &mut Context| {
+            let mut rects = Vec::new();
+
+            ctx.begin();
+
+            for (title, r) in [("A", rect(10, 10, 100, 100)), ("B", rect(200, 150, 100, 100))] {
+                Window::new(title, r).show(ctx, |ctx| rects.push(ctx.current_container().rect));
+            }
+
+            ctx.end();
+
+            rects
+        };
+
+        let drag = |ctx: &mut Context, from: Vec2, to: Vec2| {
+            ctx.input_mouse_move(from);
+            frame(ctx);
+            frame(ctx);
+            ctx.input_mouse_down(from, MouseButton::Left);
+            frame(ctx);
+            ctx.input_mouse_move(to);
+            frame(ctx);
+            ctx.input_mouse_up(to, MouseButton::Left);
+            frame(ctx)
+        };
+
+        // Drop B on the right edge of A, which splits the area of A between them.
+        drag(&mut ctx, vec2(210, 160), vec2(100, 60));
+        assert!(ctx.is_docked("A") && ctx.is_docked("B"));
+        assert_eq!(ctx.dock_tree(), None);
+        assert_eq!(frame(&mut ctx), [rect(10, 10, 48, 100), rect(62, 10, 48, 100)]);
+
+        // Pulling B out by its tab leaves A floating on its own.
+        drag(&mut ctx, vec2(70, 15), vec2(150, 200));
+        assert!(!ctx.is_docked("A") && !ctx.is_docked("B"));
+        let rects = frame(&mut ctx);
+        assert_eq!(rects[0], rect(10, 10, 48, 100));
+
+        // Dropping B onto the title bar of A makes them tabs.
+        drag(&mut ctx, vec2(rects[1].x + 10, rects[1].y + 5), vec2(30, 15));
+        assert_eq!(frame(&mut ctx), [rect(10, 10, 48, 100)]);
+        assert!(ctx.is_docked("A") && ctx.is_docked("B"));
+    }
+
+    #[test]
+    fn capacities() {
+        let windows = |ctx: &mut Context, count: usize| {
+            ctx.begin();
+
+            for i in 0..count {
+                Window::new(format!("Window {}", i), rect(0, 0, 100, 100)).show(ctx, |ctx| {
+                    ctx.label("Label");
+                });
+            }
+
+            ctx.end();
+        };
+
+        let mut ctx = Context::with_config(Handler, ContextConfig::growable());
+        windows(&mut ctx, ROOT_LIST_SIZE * 4);
+        windows(&mut ctx, ROOT_LIST_SIZE * 4);
+        assert_eq!(ctx.containers_len(), ROOT_LIST_SIZE * 4);
+
+        // Containers that weren't shown for a while are replaced instead of growing the pool.
+        for _ in 0..POOL_EVICT_FRAMES {
+            windows(&mut ctx, 1);
+        }
+
+        ctx.begin();
+        Window::new("New", rect(0, 0, 100, 100)).show(ctx.as_mut(), |_| {});
+        ctx.end();
+        assert_eq!(ctx.containers_len(), ROOT_LIST_SIZE * 4);
+
+        // The least recently used container is replaced when the pool is full.
+        let mut ctx = Context::with_config(Handler, ContextConfig::default().container_pool_size(2));
+        windows(&mut ctx, 2);
+        windows(&mut ctx, 3);
+        assert_eq!(ctx.containers_len(), 2);
+    }
+
+    #[test]
+    fn diagnostics() {
+        let config = ContextConfig::default().id_stack_size(2).diagnostics(true);
+        let mut ctx = Context::with_config(Handler, config);
+
+        ctx.begin();
+        ctx.label("Outside");
+        Window::new("Ids", rect(0, 0, 100, 100)).show(ctx.as_mut(), |ctx| {
+            for i in 0..3 {
+                ctx.push_id(&i);
+            }
+
+            ctx.button("Button");
+
+            for _ in 0..3 {
+                ctx.pop_id();
+            }
+        });
+        ctx.begin_window("Unbalanced", rect(0, 0, 100, 100), ContainerOptions::default());
+        ctx.end();
+
+        assert_eq!(ctx.errors(), [
+            UiError::OutsideRoot,
+            UiError::Unbalanced { stack: Stack::ContainerStack, container: Some("Unbalanced".into()) },
+            UiError::StackOverflow(Stack::IdStack)
+        ]);
+
+        // The unbalanced window was ended, so the frame can still be drawn.
+        let mut counter = Counter(0);
+        ctx.handle_commands(&mut counter);
+        assert!(counter.0 > 0);
+
+        ctx.begin();
+        ctx.end();
+        assert!(ctx.errors().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "You probably forgot to end \"Unbalanced\"")]
+    fn unbalanced() {
+        let mut ctx = Context::new(Handler);
+
+        ctx.begin();
+        ctx.begin_window("Unbalanced", rect(0, 0, 100, 100), ContainerOptions::default());
+        ctx.end();
+    }
+
+    #[test]
+    fn draw_outside_root() {
+        let mut ctx = Context::with_config(Handler, ContextConfig::default().diagnostics(true));
+
+        let color = Color::rgb(255, 255, 255);
+
+        ctx.begin();
+        ctx.draw_rect(rect(0, 0, 10, 10), color);
+        ctx.draw_text(FontId::default(), "Outside", vec2(0, 0), color);
+        ctx.draw_icon(Icon::Check, rect(0, 0, 10, 10), color);
+        ctx.end();
+
+        assert_eq!(ctx.errors(), [UiError::OutsideRoot]);
+        assert_eq!(ctx.commands().count(), 0);
+    }
+
+    #[test]
+    fn rounded_style() {
+        let mut ctx = Context::new(Handler);
+        ctx.style.corner_radius = 4;
+        ctx.style.border_width = 2;
+        ctx.style.shadow = Some(Shadow {
+            offset: vec2(2, 2),
+            blur: 3,
+            color: Color::rgba(0, 0, 0, 90)
+        });
+
+        ctx.begin();
+        Window::new("Rounded", rect(10, 10, 100, 100)).show(&mut ctx, |_| {});
+        ctx.end();
+
+        let rounded: Vec<_> = ctx.commands()
+            .filter_map(|cmd| match cmd {
+                DrawCommand::RoundedRect { rect, radius, color } => Some((rect, radius, color.a)),
+                _ => None
+            })
+            .collect();
+
+        // The shadow layers, the border, the background and the title bar.
+        assert_eq!(&rounded[..5], [
+            (rect(10, 10, 104, 104), 6, 30),
+            (rect(11, 11, 102, 102), 5, 30),
+            (rect(12, 12, 100, 100), 4, 30),
+            (rect(8, 8, 104, 104), 6, 255),
+            (rect(10, 10, 100, 100), 4, 255)
+        ]);
+        assert_eq!(rounded[5].0, rect(10, 10, 100, ctx.style.title_height as i32));
+    }
+
+    #[test]
+    fn fonts() {
+        // Font 1 is twice as large as the default font.
+        struct Fonts;
+
+        impl TextSizeHandler for Fonts {
+            fn text_width(&self, id: FontId, text: &str) -> i32 {
+                text.len() as i32 * 8 * (id.0 as i32 + 1)
+            }
+
+            fn text_height(&self, id: FontId) -> i32 {
+                16 * (id.0 as i32 + 1)
+            }
         }
+
+        let mut ctx = Context::new(Fonts);
+
+        ctx.begin();
+        Window::new("Fonts", rect(0, 0, 200, 200))
+            .title_font(FontId(1))
+            .show(&mut ctx, |ctx| {
+                ctx.layout_row(&[-1], 0);
+                Label::new("Heading").font(FontId(1)).draw(ctx);
+                ctx.label("Body");
+                Button::new("Go").font(FontId(1)).align(HorizontalAlign::Left).draw(ctx);
+            });
+        ctx.end();
+
+        let text: Vec<_> = ctx.commands()
+            .filter_map(|cmd| match cmd {
+                DrawCommand::Text { font, text, .. } => Some((text, font)),
+                _ => None
+            })
+            .collect();
+
+        assert_eq!(text, [
+            ("Fonts", FontId(1)),
+            ("Heading", FontId(1)),
+            ("Body", FontId(0)),
+            ("Go", FontId(1))
+        ]);
+        assert_eq!(ctx.style.font, FontId(0));
+    }
+
+    #[test]
+    fn unicode_text() {
+        let mut ctx = Context::new(Handler);
+        let text = "日本語のテキストです";
+
+        ctx.begin();
+        Window::new("Text", rect(0, 0, 100, 300)).show(&mut ctx, |ctx| {
+            ctx.text(text);
+            ctx.layout_row(&[-1], 0);
+            ctx.label("abcdefgh");
+            ctx.label("שלום");
+        });
+        ctx.end();
+
+        let commands: Vec<_> = ctx.commands()
+            .filter_map(|cmd| match cmd {
+                DrawCommand::Text { text, pos, .. } => Some((text, pos.x)),
+                _ => None
+            })
+            .skip(1)
+            .collect();
+
+        // Text without spaces is broken between characters to fit into the window.
+        let (lines, labels) = commands.split_at(commands.len() - 2);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|(line, _)| !line.is_empty() && line.len() as i32 * 8 <= 100));
+        assert_eq!(lines.iter().map(|x| x.0).collect::<String>(), text);
+
+        // Both labels are as wide, but the right-to-left one starts at the right.
+        assert!(labels[1].1 > labels[0].1);
+    }
+
+    #[test]
+    fn scale_factor() {
+        let mut ctx = Context::new(Handler);
+        ctx.set_scale_factor(1.5);
+
+        ctx.begin();
+        Window::new("Scaled", rect(10, 10, 100, 100)).show(&mut ctx, |ctx| {
+            ctx.layout_row(&[60], 20);
+            ctx.label("Body");
+        });
+        ctx.end();
+
+        let border = ctx.style.colors[WidgetColor::Border];
+        let mut rects = Vec::new();
+        let mut text = Vec::new();
+
+        for cmd in ctx.commands() {
+            match cmd {
+                DrawCommand::Rect { rect, color } => rects.push((rect, color)),
+                DrawCommand::Text { text: t, pos, .. } => text.push((t, pos)),
+                _ => {}
+            }
+        }
+
+        // The window, its title bar and the widgets are laid out in physical pixels.
+        assert_eq!(rects[0].0, rect(15, 15, 150, 150));
+        assert!(rects.iter().any(|(r, _)| *r == rect(15, 15, 150, 36)));
+
+        // Borders are whole pixels, drawn as two boxes around the window.
+        assert!(rects[1..9].iter().all(|(_, color)| *color == border));
+        assert_eq!(rects[8].0, rect(166, 13, 1, 154));
+
+        // The body is inset by the scaled padding and text is measured at 1.5 times its size.
+        assert_eq!(text[1], ("Body", vec2(15 + 8 + 8, 15 + 36 + 8 + (30 - 24) / 2)));
+
+        assert_eq!(ctx.px_line(0), 0);
+        assert_eq!(ctx.px_line(1), 2);
+        assert_eq!(ctx.px_rect(rect(1, 1, 1, 1)), rect(2, 2, 1, 1));
+    }
+
+    #[test]
+    fn scaled_fill() {
+        for scale in [1.5, 2.] {
+            let mut ctx = Context::new(Handler);
+            ctx.set_scale_factor(scale);
+
+            ctx.begin();
+            Window::new("Scaled", rect(10, 10, 190, 100)).show(&mut ctx, |ctx| {
+                let body = ctx.layout_mut().body;
+
+                // Rows of -1 reach the edge of the body, others end further in.
+                ctx.layout_row(&[-1], -1);
+                let r = ctx.layout_next();
+                assert_eq!((r.x + r.w, r.y + r.h), (body.x + body.w, body.y + body.h));
+
+                ctx.layout_row(&[20, -11], 0);
+                ctx.layout_next();
+                let r = ctx.layout_next();
+                assert_eq!(r.x + r.w, body.x + body.w - ctx.px(10));
+
+                assert_eq!(ctx.px(ctx.logical(body.w)), body.w);
+            });
+            ctx.end();
+        }
+    }
+
+    #[test]
+    fn rescale() {
+        let mut ctx = Context::new(Handler);
+        let frame = |ctx: &mut Context| {
+            let mut r = Rect::default();
+
+            ctx.begin();
+            Window::new("Scaled", rect(10, 10, 100, 100)).show(ctx, |ctx| {
+                r = ctx.current_container().rect;
+            });
+            ctx.end();
+
+            r
+        };
+
+        ctx.set_dock_area(rect(0, 0, 300, 200));
+        frame(&mut ctx);
+        ctx.set_scale_factor(1.5);
+
+        // Existing windows keep their logical rect.
+        assert_eq!(frame(&mut ctx), rect(15, 15, 150, 150));
+        assert_eq!(ctx.dock_area(), rect(0, 0, 450, 300));
+
+        let state = ctx.save_state();
+        assert_eq!(state.containers[0].rect, rect(10, 10, 100, 100));
+
+        let mut restored = Context::new(Handler);
+        restored.set_scale_factor(2.);
+        restored.load_state(&state);
+        assert_eq!(frame(&mut restored), rect(20, 20, 200, 200));
     }
 }
@@ -0,0 +1,326 @@
+use std::{fmt, fs, io, path::Path, str::FromStr};
+
+use crate::{Context, MouseButton, Key, Vec2, FrameIdx, vec2};
+
+const HEADER: &str = "microui-input 1";
+
+/// A call into one of the `Context::input_*` methods.
+#[derive(Clone, PartialEq, Debug)]
+pub enum InputEvent {
+    MouseMove(Vec2),
+    MouseDown(Vec2, MouseButton),
+    MouseUp(Vec2, MouseButton),
+    Scroll(Vec2),
+    KeyDown(Key),
+    KeyUp(Key),
+    Text(String)
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RecordedInput {
+    /// The frame the event was received in, relative to the start of the recording.
+    pub frame: FrameIdx,
+    pub event: InputEvent
+}
+
+/// Input events captured by [`Context::start_recording`].
+///
+/// Serializes to a line based text format that can be saved
+/// to a file and played back using an [`InputPlayer`].
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct InputRecording {
+    events: Vec<RecordedInput>
+}
+
+/// Replays an [`InputRecording`] into a [`Context`].
+#[derive(Clone, Debug)]
+pub struct InputPlayer {
+    recording: InputRecording,
+    next: usize,
+    start: Option<FrameIdx>
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseRecordingError {
+    /// One-based line number of the offending line.
+    pub line: usize
+}
+
+impl InputEvent {
+    pub fn apply(&self, ctx: &mut Context) {
+        match self {
+            Self::MouseMove(pos) => ctx.input_mouse_move(*pos),
+            Self::MouseDown(pos, btn) => ctx.input_mouse_down(*pos, *btn),
+            Self::MouseUp(pos, btn) => ctx.input_mouse_up(*pos, *btn),
+            Self::Scroll(delta) => ctx.input_scroll(*delta),
+            Self::KeyDown(key) => ctx.input_key_down(*key),
+            Self::KeyUp(key) => ctx.input_key_up(*key),
+            Self::Text(text) => {
+                ctx.input_text(text);
+            }
+        }
+    }
+}
+
+impl InputRecording {
+    #[inline]
+    pub fn events(&self) -> &[RecordedInput] {
+        &self.events
+    }
+
+    #[inline]
+    pub fn push(&mut self, frame: FrameIdx, event: InputEvent) {
+        self.events.push(RecordedInput { frame, event });
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl InputPlayer {
+    #[inline]
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            recording,
+            next: 0,
+            start: None
+        }
+    }
+
+    /// Feeds all events recorded up to the current frame into `ctx`.
+    /// Must be called before every [`Context::begin`], starting with
+    /// the first frame that should receive input.
+    pub fn play(&mut self, ctx: &mut Context) {
+        let start = *self.start.get_or_insert(ctx.current_frame());
+        let frame = ctx.current_frame() - start;
+
+        while let Some(input) = self.recording.events.get(self.next) {
+            if input.frame > frame {
+                break;
+            }
+
+            input.event.apply(ctx);
+            self.next += 1;
+        }
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.events.len()
+    }
+}
+
+impl fmt::Display for InputRecording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+
+        for input in &self.events {
+            write!(f, "{} ", input.frame)?;
+
+            match &input.event {
+                InputEvent::MouseMove(p) => writeln!(f, "mouse_move {} {}", p.x, p.y)?,
+                InputEvent::MouseDown(p, btn) => writeln!(f, "mouse_down {} {} {:?}", p.x, p.y, btn)?,
+                InputEvent::MouseUp(p, btn) => writeln!(f, "mouse_up {} {} {:?}", p.x, p.y, btn)?,
+                InputEvent::Scroll(d) => writeln!(f, "scroll {} {}", d.x, d.y)?,
+                InputEvent::KeyDown(key) => writeln!(f, "key_down {:?}", key)?,
+                InputEvent::KeyUp(key) => writeln!(f, "key_up {:?}", key)?,
+                InputEvent::Text(text) => {
+                    f.write_str("text ")?;
+                    write_escaped(f, text)?;
+                    writeln!(f)?
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for InputRecording {
+    type Err = ParseRecordingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+
+        if lines.next() != Some(HEADER) {
+            return Err(ParseRecordingError { line: 1 });
+        }
+
+        let mut recording = Self::default();
+
+        for (i, line) in lines.enumerate() {
+            let err = ParseRecordingError { line: i + 2 };
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let (frame, rest) = line.split_once(' ').ok_or(err.clone())?;
+            let frame = frame.parse().map_err(|_| err.clone())?;
+            let (name, args) = rest.split_once(' ').unwrap_or((rest, ""));
+
+            let event = if name == "text" {
+                InputEvent::Text(unescape(args).ok_or(err)?)
+            } else {
+                parse_event(name, args).ok_or(err)?
+            };
+
+            recording.push(frame, event);
+        }
+
+        Ok(recording)
+    }
+}
+
+impl fmt::Display for ParseRecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid input recording at line {}.", self.line)
+    }
+}
+
+impl std::error::Error for ParseRecordingError {}
+
+fn parse_event(name: &str, args: &str) -> Option<InputEvent> {
+    let args: Vec<&str> = args.split(' ').collect();
+
+    let pos = || -> Option<Vec2> {
+        Some(vec2(args.first()?.parse().ok()?, args.get(1)?.parse().ok()?))
+    };
+
+    let btn = || -> Option<MouseButton> {
+        match *args.get(2)? {
+            "Left" => Some(MouseButton::Left),
+            "Right" => Some(MouseButton::Right),
+            "Middle" => Some(MouseButton::Middle),
+            _ => None
+        }
+    };
+
+    let key = || -> Option<Key> {
+        args.first()?.parse().ok()
+    };
+
+    let event = match name {
+        "mouse_move" => InputEvent::MouseMove(pos()?),
+        "mouse_down" => InputEvent::MouseDown(pos()?, btn()?),
+        "mouse_up" => InputEvent::MouseUp(pos()?, btn()?),
+        "scroll" => InputEvent::Scroll(pos()?),
+        "key_down" => InputEvent::KeyDown(key()?),
+        "key_up" => InputEvent::KeyUp(key()?),
+        _ => return None
+    };
+
+    Some(event)
+}
+
+/// Writes `text` with backslashes and line breaks escaped so that it fits on a single line.
+pub(crate) fn write_escaped(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+    for c in text.chars() {
+        match c {
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            c => write!(f, "{}", c)?
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn unescape(text: &str) -> Option<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+
+            continue;
+        }
+
+        match chars.next()? {
+            '\\' => result.push('\\'),
+            'n' => result.push('\n'),
+            'r' => result.push('\r'),
+            _ => return None
+        }
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Handler;
+
+    #[test]
+    fn round_trip() {
+        let mut recording = InputRecording::default();
+        recording.push(0, InputEvent::MouseMove(vec2(-1, 2)));
+        recording.push(0, InputEvent::MouseDown(vec2(3, 4), MouseButton::Right));
+        recording.push(2, InputEvent::MouseUp(vec2(3, 4), MouseButton::Right));
+        recording.push(2, InputEvent::Scroll(vec2(0, -30)));
+        recording.push(5, InputEvent::KeyDown(Key::Shift));
+        recording.push(5, InputEvent::Text("a b\\c\nd".into()));
+        recording.push(6, InputEvent::KeyUp(Key::Shift));
+
+        let text = recording.to_string();
+        assert_eq!(text.parse::<InputRecording>().unwrap(), recording);
+
+        assert_eq!(
+            "microui-input 1\n0 mouse_move 1".parse::<InputRecording>(),
+            Err(ParseRecordingError { line: 2 })
+        );
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let mut ctx = Context::new(Handler);
+        ctx.begin();
+        ctx.end();
+
+        ctx.start_recording();
+
+        ctx.input_mouse_down(vec2(10, 10), MouseButton::Left);
+        ctx.begin();
+        ctx.end();
+        ctx.begin();
+        ctx.end();
+        ctx.input_mouse_up(vec2(20, 10), MouseButton::Left);
+        ctx.input_text("x");
+
+        let recording = ctx.stop_recording().unwrap();
+        assert!(!ctx.is_recording());
+        assert_eq!(recording.events(), &[
+            RecordedInput { frame: 0, event: InputEvent::MouseDown(vec2(10, 10), MouseButton::Left) },
+            RecordedInput { frame: 2, event: InputEvent::MouseUp(vec2(20, 10), MouseButton::Left) },
+            RecordedInput { frame: 2, event: InputEvent::Text("x".into()) }
+        ]);
+
+        let mut ctx = Context::new(Handler);
+        let mut player = InputPlayer::new(recording);
+
+        player.play(&mut ctx);
+        assert!(ctx.mouse_down(MouseButton::Left));
+        ctx.begin();
+        ctx.end();
+
+        player.play(&mut ctx);
+        ctx.begin();
+        ctx.end();
+        assert!(!player.is_finished());
+
+        player.play(&mut ctx);
+        assert!(player.is_finished());
+        assert!(!ctx.mouse_down(MouseButton::Left));
+        assert_eq!(ctx.mouse_pos(), vec2(20, 10));
+    }
+}
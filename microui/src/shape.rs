@@ -0,0 +1,155 @@
+//! Rasterizes shapes into rects for renderers that can only fill rects.
+//! These are used by the default methods of [`CommandHandler`](crate::CommandHandler)
+//! and call `f` with every rect to fill.
+
+use crate::{Angle, Rect, Vec2, rect};
+
+/// Steps from `a` to `b` with squares as wide as the line.
+pub fn line(a: Vec2, b: Vec2, thickness: i32, mut f: impl FnMut(Rect)) {
+    let t = thickness.max(1);
+    let steps = (b.x - a.x).abs().max((b.y - a.y).abs()).max(1);
+
+    for i in 0..=steps {
+        let x = a.x + (b.x - a.x) * i / steps;
+        let y = a.y + (b.y - a.y) * i / steps;
+
+        f(rect(x - t / 2, y - t / 2, t, t));
+    }
+}
+
+pub fn rounded_rect(r: Rect, radius: i32, mut f: impl FnMut(Rect)) {
+    let radius = radius.clamp(0, r.w.min(r.h) / 2);
+
+    f(rect(r.x, r.y + radius, r.w, r.h - radius * 2));
+
+    for i in 0..radius {
+        let inset = radius - circle_width(radius, radius - i);
+
+        f(rect(r.x + inset, r.y + i, r.w - inset * 2, 1));
+        f(rect(r.x + inset, r.y + r.h - 1 - i, r.w - inset * 2, 1));
+    }
+}
+
+pub fn circle(center: Vec2, radius: i32, mut f: impl FnMut(Rect)) {
+    for i in 0..radius {
+        let half = circle_width(radius, radius - i);
+
+        if half > 0 {
+            f(rect(center.x - half, center.y - radius + i, half * 2, 1));
+            f(rect(center.x - half, center.y + radius - 1 - i, half * 2, 1));
+        }
+    }
+}
+
+/// Steps along the arc from `start` to `end` with squares as wide as the line.
+pub fn arc(
+    center: Vec2,
+    radius: i32,
+    start: Angle,
+    end: Angle,
+    thickness: i32,
+    mut f: impl FnMut(Rect)
+) {
+    let t = thickness.max(1);
+    let sweep = end.0 - start.0;
+    let steps = (sweep.abs() * radius as f32).ceil().max(1.) as i32;
+
+    for i in 0..=steps {
+        let a = start.0 + sweep * i as f32 / steps as f32;
+        let x = center.x + (a.cos() * radius as f32).round() as i32;
+        let y = center.y + (a.sin() * radius as f32).round() as i32;
+
+        f(rect(x - t / 2, y - t / 2, t, t));
+    }
+}
+
+/// Fills the pixels whose centers are inside the polygon with the even-odd rule.
+pub fn polygon(points: &[Vec2], mut f: impl FnMut(Rect)) {
+    if points.len() < 3 {
+        return;
+    }
+
+    let bounds = Rect::bounding(points.iter().copied());
+    let mut xs = Vec::new();
+
+    for y in bounds.y..bounds.y + bounds.h {
+        let sy = y as f32 + 0.5;
+        xs.clear();
+
+        for (i, p) in points.iter().enumerate() {
+            let q = points[(i + 1) % points.len()];
+            let (py, qy) = (p.y as f32, q.y as f32);
+
+            if (py <= sy) != (qy <= sy) {
+                xs.push(p.x as f32 + (sy - py) * (q.x - p.x) as f32 / (qy - py));
+            }
+        }
+
+        xs.sort_by(f32::total_cmp);
+
+        for pair in xs.chunks_exact(2) {
+            let x0 = (pair[0] - 0.5).ceil() as i32;
+            let x1 = (pair[1] - 0.5).ceil() as i32;
+
+            if x1 > x0 {
+                f(rect(x0, y, x1 - x0, 1));
+            }
+        }
+    }
+}
+
+/// Half of the width of a circle in the row that is `dy` pixels from its center.
+fn circle_width(radius: i32, dy: i32) -> i32 {
+    let dy = dy as f32 - 0.5;
+
+    (radius as f32 * radius as f32 - dy * dy).max(0.).sqrt().round() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec2;
+
+    fn area(draw: impl FnOnce(&mut dyn FnMut(Rect))) -> i32 {
+        let mut area = 0;
+        draw(&mut |r: Rect| area += r.w.max(0) * r.h.max(0));
+
+        area
+    }
+
+    #[test]
+    fn polygon_fill() {
+        let square = [vec2(0, 0), vec2(10, 0), vec2(10, 10), vec2(0, 10)];
+        assert_eq!(area(|f| polygon(&square, f)), 100);
+
+        // Pixels whose centers are on the diagonal are left out.
+        let triangle = [vec2(0, 0), vec2(10, 0), vec2(0, 10)];
+        assert_eq!(area(|f| polygon(&triangle, f)), 45);
+
+        assert_eq!(area(|f| polygon(&square[..2], f)), 0);
+    }
+
+    #[test]
+    fn round_shapes() {
+        let circle_area = area(|f| circle(vec2(50, 50), 10, f));
+        assert!((circle_area - 314).abs() < 10, "{}", circle_area);
+
+        // A rounded rect is a rect without the parts of its corners outside of a circle.
+        let rounded = area(|f| rounded_rect(rect(0, 0, 40, 20), 10, f));
+        assert_eq!(rounded, 40 * 20 - 20 * 20 + circle_area);
+
+        assert_eq!(area(|f| rounded_rect(rect(0, 0, 40, 20), 0, f)), 40 * 20);
+    }
+
+    #[test]
+    fn lines() {
+        let mut rects = Vec::new();
+        line(vec2(0, 0), vec2(4, 0), 2, |r| rects.push(r));
+        assert_eq!(rects.len(), 5);
+        assert!(rects.iter().all(|r| r.y == -1 && r.h == 2));
+
+        let mut last = None;
+        arc(vec2(0, 0), 10, Angle::ZERO, Angle::degrees(90.), 1, |r| last = Some(r));
+        assert_eq!(last, Some(rect(0, 10, 1, 1)));
+    }
+}
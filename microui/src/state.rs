@@ -0,0 +1,392 @@
+use std::{fmt, fs, io, path::Path, str::FromStr};
+
+use crate::{
+    Context, Id, Rect, Vec2, DockNode, DockAxis, rect, vec2,
+    recording::{write_escaped, unescape}
+};
+
+const HEADER: &str = "microui-state";
+
+/// The persistent state of a container, i.e. a window. The rect and scroll
+/// offset are in logical pixels, so that they don't depend on the scale factor
+/// of the screen the state was saved on.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ContainerState {
+    pub id: Id,
+    pub rect: Rect,
+    pub scroll: Vec2,
+    pub zindex: isize,
+    pub open: bool
+}
+
+/// The state of the containers, tree nodes and docked windows of a [`Context`]
+/// as returned by [`Context::save_state`].
+///
+/// Serializes to a line based text format that starts with the
+/// [`UiState::VERSION`] it was written with.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct UiState {
+    pub containers: Vec<ContainerState>,
+    /// The ids of the expanded tree nodes and headers.
+    pub treenodes: Vec<Id>,
+    pub dock: Option<DockNode>
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseStateError {
+    /// One-based line number of the offending line.
+    pub line: usize
+}
+
+/// A dock tree node in the order it's written in.
+enum DockItem {
+    Split(DockAxis, f32),
+    Tabs(usize, Vec<String>)
+}
+
+impl UiState {
+    pub const VERSION: u32 = 1;
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl Context {
+    /// Captures the state that should survive a restart. Popups, like menus
+    /// and dropdowns, and internal containers, whose names start with `!`,
+    /// are left out. Windows docked onto floating windows are restored as
+    /// floating windows.
+    pub fn save_state(&self) -> UiState {
+        let containers = self.container_pool.iter()
+            .zip(self.containers.iter())
+            .filter(|(item, container)| {
+                item.last_update > 0 && !container.popup && !container.name.starts_with('!')
+            })
+            .map(|(item, container)| ContainerState {
+                id: item.id,
+                rect: self.logical_rect(container.rect),
+                scroll: vec2(self.logical(container.scroll.x), self.logical(container.scroll.y)),
+                zindex: container.zindex,
+                open: container.open
+            })
+            .collect();
+
+        let treenodes = self.treenode_pool.iter()
+            .filter(|item| item.last_update > 0)
+            .map(|item| item.id)
+            .collect();
+
+        UiState {
+            containers,
+            treenodes,
+            dock: self.dock.root.clone()
+        }
+    }
+
+    /// Restores state captured by [`Context::save_state`]. This should be
+    /// called before the first frame, otherwise the state of containers
+    /// that already exist is overwritten.
+    pub fn load_state(&mut self, state: &UiState) {
+        // Mark the items as used in the next frame so they aren't
+        // replaced by new items before they get a chance to be used.
+        let frame = self.frame + 1;
+
+        for saved in &state.containers {
+            let rect = self.px_rect(saved.rect);
+            let scroll = vec2(self.px(saved.scroll.x), self.px(saved.scroll.y));
+            let index = self.container_pool.find_by_id(saved.id)
+                .or_else(|| self.init_container_pool(saved.id));
+
+            if let Some(index) = index {
+                self.container_pool[index].last_update = frame;
+
+                let container = &mut self.containers[index];
+                container.rect = rect;
+                container.scroll = scroll;
+                container.zindex = saved.zindex;
+                container.open = saved.open;
+
+                self.last_zindex = self.last_zindex.max(saved.zindex);
+            }
+        }
+
+        for id in &state.treenodes {
+            if self.treenode_pool.find_by_id(*id).is_none() {
+                self.treenode_pool.init(*id, frame);
+            }
+        }
+
+        self.set_dock_tree(state.dock.clone());
+    }
+}
+
+impl fmt::Display for UiState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", HEADER, Self::VERSION)?;
+
+        for c in &self.containers {
+            writeln!(
+                f,
+                "container {} {} {} {} {} {} {} {} {}",
+                c.id,
+                c.rect.x, c.rect.y, c.rect.w, c.rect.h,
+                c.scroll.x, c.scroll.y,
+                c.zindex,
+                c.open as u8
+            )?;
+        }
+
+        for id in &self.treenodes {
+            writeln!(f, "treenode {}", id)?;
+        }
+
+        if let Some(root) = &self.dock {
+            write_dock(f, root)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for UiState {
+    type Err = ParseStateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+
+        if lines.next() != Some(&format!("{} {}", HEADER, Self::VERSION)) {
+            return Err(ParseStateError { line: 1 });
+        }
+
+        let mut state = Self::default();
+        let mut dock = Vec::new();
+
+        for (i, line) in lines.enumerate() {
+            let err = ParseStateError { line: i + 2 };
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let (name, args) = line.split_once(' ').unwrap_or((line, ""));
+
+            match name {
+                "window" => match dock.last_mut() {
+                    Some((_, DockItem::Tabs(_, windows))) => windows.push(unescape(args).ok_or(err)?),
+                    _ => return Err(err)
+                },
+                "split" | "tabs" => dock.push((err.line, parse_dock_item(name, args).ok_or(err)?)),
+                _ => parse_line(&mut state, name, args).ok_or(err)?
+            }
+        }
+
+        if !dock.is_empty() {
+            let mut items = dock.into_iter();
+            let root = build_dock(&mut items, 1)?;
+
+            if let Some((line, _)) = items.next() {
+                return Err(ParseStateError { line });
+            }
+
+            state.dock = Some(root);
+        }
+
+        Ok(state)
+    }
+}
+
+impl fmt::Display for ParseStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid UI state at line {}.", self.line)
+    }
+}
+
+impl std::error::Error for ParseStateError {}
+
+fn write_dock(f: &mut fmt::Formatter<'_>, node: &DockNode) -> fmt::Result {
+    match node {
+        DockNode::Split { axis, ratio, first, second } => {
+            writeln!(f, "split {:?} {}", axis, ratio)?;
+            write_dock(f, first)?;
+            write_dock(f, second)
+        },
+        DockNode::Tabs { windows, active } => {
+            writeln!(f, "tabs {}", active)?;
+
+            for window in windows {
+                f.write_str("window ")?;
+                write_escaped(f, window)?;
+                writeln!(f)?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn parse_line(state: &mut UiState, name: &str, args: &str) -> Option<()> {
+    let args: Vec<&str> = args.split(' ').collect();
+    let arg = |i: usize| -> Option<i32> { args.get(i)?.parse().ok() };
+
+    match name {
+        "container" if args.len() == 9 => state.containers.push(ContainerState {
+            id: Id(args[0].parse().ok()?),
+            rect: rect(arg(1)?, arg(2)?, arg(3)?, arg(4)?),
+            scroll: vec2(arg(5)?, arg(6)?),
+            zindex: args[7].parse().ok()?,
+            open: match args[8] {
+                "0" => false,
+                "1" => true,
+                _ => return None
+            }
+        }),
+        "treenode" if args.len() == 1 => state.treenodes.push(Id(args[0].parse().ok()?)),
+        _ => return None
+    }
+
+    Some(())
+}
+
+fn parse_dock_item(name: &str, args: &str) -> Option<DockItem> {
+    let args: Vec<&str> = args.split(' ').collect();
+
+    let item = match (name, args.as_slice()) {
+        ("split", [axis, ratio]) => {
+            let axis = match *axis {
+                "Horizontal" => DockAxis::Horizontal,
+                "Vertical" => DockAxis::Vertical,
+                _ => return None
+            };
+
+            DockItem::Split(axis, ratio.parse().ok()?)
+        },
+        ("tabs", [active]) => DockItem::Tabs(active.parse().ok()?, Vec::new()),
+        _ => return None
+    };
+
+    Some(item)
+}
+
+/// Builds a node from the items written by `write_dock`. A missing node is
+/// reported at the line of the split it belongs to.
+fn build_dock(
+    items: &mut impl Iterator<Item = (usize, DockItem)>,
+    parent_line: usize
+) -> Result<DockNode, ParseStateError> {
+    let (line, item) = items.next().ok_or(ParseStateError { line: parent_line })?;
+
+    let node = match item {
+        DockItem::Split(axis, ratio) => {
+            let first = build_dock(items, line)?;
+            let second = build_dock(items, line)?;
+
+            DockNode::split(axis, ratio, first, second)
+        },
+        DockItem::Tabs(active, windows) => {
+            if active >= windows.len() {
+                return Err(ParseStateError { line });
+            }
+
+            DockNode::Tabs { windows, active }
+        }
+    };
+
+    Ok(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Window, Popup, Treenode, MouseButton};
+    use crate::test_util::Handler;
+
+    fn frame(ctx: &mut Context) -> Option<Rect> {
+        let mut r = None;
+
+        ctx.begin();
+        Window::new("Window", rect(10, 10, 200, 200)).show(ctx, |ctx| {
+            r = Some(ctx.current_container().rect);
+
+            Treenode::new("Node").show(ctx, |_| {});
+        });
+        ctx.end();
+
+        r
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut ctx = Context::new(Handler);
+        frame(&mut ctx);
+
+        // Move the window and expand the tree node.
+        ctx.input_mouse_move(vec2(20, 20));
+        frame(&mut ctx);
+        frame(&mut ctx);
+        ctx.input_mouse_down(vec2(20, 20), MouseButton::Left);
+        frame(&mut ctx);
+        ctx.input_mouse_move(vec2(50, 30));
+        frame(&mut ctx);
+        ctx.input_mouse_up(vec2(50, 30), MouseButton::Left);
+        ctx.input_mouse_move(vec2(60, 55));
+        frame(&mut ctx);
+        frame(&mut ctx);
+        ctx.input_mouse_down(vec2(60, 55), MouseButton::Left);
+        frame(&mut ctx);
+        ctx.input_mouse_up(vec2(60, 55), MouseButton::Left);
+        frame(&mut ctx);
+
+        ctx.set_dock_tree(Some(DockNode::split(
+            DockAxis::Vertical,
+            0.25,
+            DockNode::tabs(["Other"]),
+            DockNode::Tabs { windows: vec!["A b\\c".into(), "D".into()], active: 1 }
+        )));
+
+        let state = ctx.save_state();
+        assert_eq!(state.treenodes.len(), 1);
+
+        let text = state.to_string();
+        assert_eq!(text.parse::<UiState>().unwrap(), state);
+
+        let mut restored = Context::new(Handler);
+        restored.load_state(&text.parse().unwrap());
+        assert_eq!(frame(&mut restored), Some(rect(40, 20, 200, 200)));
+        assert_eq!(restored.save_state().treenodes, state.treenodes);
+        assert_eq!(restored.dock_tree(), state.dock.as_ref());
+
+        assert_eq!("microui-state 2".parse::<UiState>(), Err(ParseStateError { line: 1 }));
+        assert_eq!("microui-state 1\nsplit Vertical 0.5\ntabs 0\nwindow A".parse::<UiState>(), Err(ParseStateError { line: 2 }));
+        assert_eq!("microui-state 1\ntabs 0".parse::<UiState>(), Err(ParseStateError { line: 2 }));
+    }
+
+    #[test]
+    fn skip_popups() {
+        let mut ctx = Context::new(Handler);
+
+        for i in 0..3 {
+            ctx.begin();
+            Window::new("Window", rect(10, 10, 200, 200)).show(&mut ctx, |ctx| {
+                let popup = Popup::new("Popup");
+
+                if i == 0 {
+                    popup.open(ctx);
+                }
+
+                popup.show(ctx, |ctx| ctx.label("Item"));
+            });
+            Window::new("!internal", rect(10, 10, 200, 200)).show(&mut ctx, |_| {});
+            ctx.end();
+        }
+
+        let ids: Vec<Id> = ctx.save_state().containers.iter().map(|c| c.id).collect();
+        assert_eq!(ids, [ctx.create_id(&"Window")]);
+    }
+}
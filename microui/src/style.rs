@@ -34,7 +34,12 @@ pub enum WidgetColor {
     BaseHover = Self::Base as u8 + 1u8,
     BaseFocus = Self::Base as u8 + 2u8,
     ScrollBase = 12,
-    ScrollThumb = 13
+    ScrollThumb = 13,
+    TextSelection = 14,
+    FocusRing = 15,
+    DockPreview = 16,
+    /// The text of disabled menu items.
+    TextDisabled = 17
 }
 
 pub struct Style {
@@ -47,9 +52,22 @@ pub struct Style {
     pub footer_height: u16,
     pub scrollbar_size: u16,
     pub thumb_size: u16,
+    /// Rounds the corners of windows, popups and widget frames.
+    pub corner_radius: u16,
+    pub border_width: u16,
+    /// Drawn behind windows and popups that aren't docked.
+    pub shadow: Option<Shadow>,
     pub colors: WidgetColors
 }
 
+/// A drop shadow that is `blur` pixels wider than the window and moved by `offset`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Shadow {
+    pub offset: Vec2,
+    pub blur: u16,
+    pub color: Color
+}
+
 impl Color {
     pub const TRANSPARENT: Color = Self::rgba(0, 0, 0, 0);
 
@@ -96,6 +114,9 @@ impl Default for Style {
             footer_height: 20,
             scrollbar_size: 12,
             thumb_size: 8,
+            corner_radius: 0,
+            border_width: 1,
+            shadow: None,
             colors: WidgetColors::default()
         }
     }
@@ -120,6 +141,10 @@ impl Default for WidgetColors {
         c[BaseFocus] = Color::rgb(40, 40, 40);
         c[ScrollBase] = Color::rgb(43, 43, 43);
         c[ScrollThumb] = Color::rgb(30, 30, 30);
+        c[TextSelection] = Color::rgb(60, 90, 135);
+        c[FocusRing] = Color::rgb(90, 140, 210);
+        c[DockPreview] = Color::rgba(90, 140, 210, 80);
+        c[TextDisabled] = Color::rgb(120, 120, 120);
 
         c
     }
@@ -0,0 +1,37 @@
+//! Fixtures shared by the tests of the crate.
+
+use crate::{TextSizeHandler, CommandHandler, FontId, Icon, Rect, Vec2, Color};
+
+/// Measures every byte as 8 pixels wide and lines as 16 pixels high.
+pub(crate) struct Handler;
+
+impl TextSizeHandler for Handler {
+    fn text_width(&self, _id: FontId, text: &str) -> i32 {
+        text.len() as i32 * 8
+    }
+
+    fn text_height(&self, _id: FontId) -> i32 {
+        16
+    }
+}
+
+/// Counts the commands it handles.
+pub(crate) struct Counter(pub usize);
+
+impl CommandHandler for Counter {
+    fn clip_cmd(&mut self, _rect: Rect) {
+        self.0 += 1;
+    }
+
+    fn rect_cmd(&mut self, _rect: Rect, _color: Color) {
+        self.0 += 1;
+    }
+
+    fn text_cmd(&mut self, _font: FontId, _pos: Vec2, _color: Color, _text: &str) {
+        self.0 += 1;
+    }
+
+    fn icon_cmd(&mut self, _id: Icon, _rect: Rect, _color: Color) {
+        self.0 += 1;
+    }
+}
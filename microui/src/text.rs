@@ -0,0 +1,96 @@
+//! Unicode helpers shared by the text widgets and the renderers.
+//! Carets move and text breaks between grapheme clusters, so that
+//! characters with combining marks or emoji sequences stay together.
+
+use std::borrow::Cow;
+
+use unicode_bidi::BidiInfo;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The start of the grapheme cluster before the byte index `pos`.
+#[inline]
+pub fn prev_grapheme(text: &str, pos: usize) -> usize {
+    text[..pos].grapheme_indices(true).next_back().map_or(0, |x| x.0)
+}
+
+/// The end of the grapheme cluster after the byte index `pos`.
+#[inline]
+pub fn next_grapheme(text: &str, pos: usize) -> usize {
+    text[pos..].graphemes(true).next().map_or(pos, |g| pos + g.len())
+}
+
+/// The byte indices at which a caret can be placed, from `0` to `text.len()`.
+pub fn grapheme_boundaries(text: &str) -> impl Iterator<Item = usize> + '_ {
+    text.grapheme_indices(true).map(|x| x.0).chain([text.len()])
+}
+
+/// `true` if the first strong character of `text` is written right-to-left,
+/// i.e. Hebrew or Arabic.
+pub fn is_rtl(text: &str) -> bool {
+    if !may_contain_rtl(text) {
+        return false;
+    }
+
+    BidiInfo::new(text, None).paragraphs.first().is_some_and(|p| p.level.is_rtl())
+}
+
+/// Reorders `text` from the order it's stored in to the order its characters
+/// are drawn in from left to right. Renderers that don't reorder text themselves
+/// should draw and measure the result, so that i.e. kerning is applied between
+/// the glyphs that end up next to each other.
+pub fn visual_order(text: &str) -> Cow<'_, str> {
+    if !may_contain_rtl(text) {
+        return Cow::Borrowed(text);
+    }
+
+    let info = BidiInfo::new(text, None);
+
+    if !info.has_rtl() {
+        return Cow::Borrowed(text);
+    }
+
+    info.paragraphs.iter()
+        .map(|para| info.reorder_line(para, para.range.clone()))
+        .collect::<String>()
+        .into()
+}
+
+/// Right-to-left scripts start with Hebrew, so text without anything after
+/// it doesn't need the bidirectional algorithm.
+#[inline]
+fn may_contain_rtl(text: &str) -> bool {
+    text.chars().any(|c| c >= '\u{590}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graphemes() {
+        // An e with a combining acute accent and a family emoji made of joined emojis.
+        let text = "e\u{301}x👨‍👩‍👧";
+
+        assert_eq!(next_grapheme(text, 0), 3);
+        assert_eq!(next_grapheme(text, 3), 4);
+        assert_eq!(next_grapheme(text, 4), text.len());
+        assert_eq!(next_grapheme(text, text.len()), text.len());
+
+        assert_eq!(prev_grapheme(text, text.len()), 4);
+        assert_eq!(prev_grapheme(text, 3), 0);
+        assert_eq!(prev_grapheme(text, 0), 0);
+
+        assert_eq!(grapheme_boundaries(text).collect::<Vec<_>>(), [0, 3, 4, text.len()]);
+    }
+
+    #[test]
+    fn bidi() {
+        assert!(!is_rtl("abc"));
+        assert!(is_rtl("שלום abc"));
+        assert!(!is_rtl("abc שלום"));
+
+        assert!(matches!(visual_order("abc"), Cow::Borrowed("abc")));
+        assert_eq!(visual_order("אבג"), "גבא");
+        assert_eq!(visual_order("x אב y"), "x בא y");
+    }
+}
@@ -1,11 +1,17 @@
-use std::{ptr, fmt};
+use std::{ptr, fmt, ops::Range};
 
-use crate::const_vec::ConstStr;
+use crate::{const_vec::ConstStr, text::prev_grapheme};
 
 pub trait TextBuf: fmt::Write {
     fn as_str(&self) -> &str;
     fn push_str(&mut self, text: &str) -> usize;
+    /// Removes the last grapheme cluster, i.e. a letter with its accents.
     fn pop_char(&mut self);
+    /// `index` must be on a character boundary.
+    /// Returns the number of bytes written.
+    fn insert_str(&mut self, index: usize, text: &str) -> usize;
+    /// Both ends of `range` must be on a character boundary.
+    fn remove(&mut self, range: Range<usize>);
 }
 
 impl<const N: usize> fmt::Write for ConstStr<N> {
@@ -24,7 +30,14 @@ impl<const N: usize> fmt::Write for ConstStr<N> {
 }
 
 impl<const N: usize> TextBuf for ConstStr<N> {
+    #[inline]
     fn push_str(&mut self, text: &str) -> usize {
+        self.insert_str(self.len(), text)
+    }
+
+    fn insert_str(&mut self, index: usize, text: &str) -> usize {
+        assert!(self.as_str().is_char_boundary(index), "Index is not a char boundary.");
+
         if text.is_empty() {
             return 0;
         }
@@ -51,31 +64,42 @@ impl<const N: usize> TextBuf for ConstStr<N> {
         };
 
         if count > 0 {
+            let len = self.len();
+
             unsafe {
-                ptr::copy_nonoverlapping(
-                    bytes.as_ptr(),
-                    self.ptr_at_mut(self.len()),
-                    count
-                );
+                let ptr = self.as_mut_ptr();
+
+                ptr::copy(ptr.add(index), ptr.add(index + count), len - index);
+                ptr::copy_nonoverlapping(bytes.as_ptr(), ptr.add(index), count);
     
-                self.set_len(self.len() + count);
+                self.set_len(len + count);
             }
         }
 
         count
     }
 
-    fn pop_char(&mut self) {
-        let mut len = self.len();
+    fn remove(&mut self, range: Range<usize>) {
+        let text = self.as_str();
+        assert!(
+            range.start <= range.end &&
+            text.is_char_boundary(range.start) &&
+            text.is_char_boundary(range.end),
+            "Range is not on char boundaries."
+        );
 
-        // Skip utf-8 continuation bytes (multi-byte characters).
-        while len > 0 {
-            len -= 1;
+        let len = self.len();
 
-            if (self[len] as i8) >= -0x40 {
-                break;
-            }
+        unsafe {
+            let ptr = self.as_mut_ptr();
+            ptr::copy(ptr.add(range.end), ptr.add(range.start), len - range.end);
+
+            self.set_len(len - range.len());
         }
+    }
+
+    fn pop_char(&mut self) {
+        let len = prev_grapheme(self.as_str(), self.len());
 
         unsafe { self.set_len(len) }
     }
@@ -98,7 +122,19 @@ impl TextBuf for String {
 
     #[inline]
     fn pop_char(&mut self) {
-        self.pop();
+        self.truncate(prev_grapheme(self, self.len()));
+    }
+
+    #[inline]
+    fn insert_str(&mut self, index: usize, text: &str) -> usize {
+        self.insert_str(index, text);
+
+        text.len()
+    }
+
+    #[inline]
+    fn remove(&mut self, range: Range<usize>) {
+        self.replace_range(range, "");
     }
 
     #[inline]
@@ -138,6 +174,15 @@ mod tests {
 
         assert_eq!(vec.len(), 0);
         assert_eq!(vec.as_str(), "");
+
+        // Combining characters are removed with the character before them.
+        let mut text = String::from("ae\u{301}");
+        vec.push_str(&text);
+
+        vec.pop_char();
+        TextBuf::pop_char(&mut text);
+        assert_eq!(vec.as_str(), "a");
+        assert_eq!(text, "a");
     }
 
     #[test]
@@ -184,4 +229,39 @@ mod tests {
         assert_eq!(vec.len(), 9);
         assert_eq!(vec.as_str(), [chars, "X"].concat());
     }
+
+    #[test]
+    fn insert_str() {
+        let mut vec = ConstStr::<9>::new();
+
+        assert_eq!(vec.insert_str(0, "ac"), 2);
+        assert_eq!(vec.insert_str(1, "ß"), 2);
+        assert_eq!(vec.as_str(), "aßc");
+
+        assert_eq!(vec.insert_str(0, "東"), 3);
+        assert_eq!(vec.as_str(), "東aßc");
+
+        assert_eq!(vec.insert_str(vec.len(), "東"), 0);
+        assert_eq!(vec.insert_str(3, "XY"), 2);
+        assert_eq!(vec.as_str(), "東XYaßc");
+        assert_eq!(vec.len(), 9);
+    }
+
+    #[test]
+    fn remove() {
+        let mut vec = ConstStr::<16>::new();
+        vec.push_str("aß東💩b");
+
+        vec.remove(1..3);
+        assert_eq!(vec.as_str(), "a東💩b");
+
+        vec.remove(4..8);
+        assert_eq!(vec.as_str(), "a東b");
+
+        vec.remove(0..0);
+        assert_eq!(vec.as_str(), "a東b");
+
+        vec.remove(0..vec.len());
+        assert_eq!(vec.as_str(), "");
+    }
 }
@@ -1,12 +1,13 @@
 use crate::{
     Context, ContainerOptions, ContainerOption, MouseButton,
-    Icon, WidgetInteraction, WidgetColor, Response
+    Icon, WidgetInteraction, WidgetColor, Response, FontId
 };
 use super::{Widget, HorizontalAlign};
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct Button {
     content: Content,
+    font: Option<FontId>,
     options: ContainerOptions,
     hand_cursor: bool
 }
@@ -22,6 +23,7 @@ impl Button {
     pub fn new(text: impl Into<String>) -> Self {
         Self {
             content: Content::Text(text.into()),
+            font: None,
             options: ContainerOptions(ContainerOption::AlignCenter as u16),
             hand_cursor: false
         }
@@ -31,6 +33,7 @@ impl Button {
     pub fn icon(icon: Icon) -> Self {
         Self {
             content: Content::Icon(icon),
+            font: None,
             options: ContainerOptions(ContainerOption::AlignCenter as u16),
             hand_cursor: false
         }
@@ -40,6 +43,7 @@ impl Button {
     pub fn empty() -> Self {
         Self {
             content: Content::Icon(Icon::None),
+            font: None,
             options: ContainerOptions(ContainerOption::AlignCenter as u16),
             hand_cursor: false
         }
@@ -52,6 +56,14 @@ impl Button {
         self
     }
 
+    /// Draws the text with `font` instead of the style's font.
+    #[inline]
+    pub fn font(mut self, font: FontId) -> Self {
+        self.font = Some(font);
+
+        self
+    }
+
     /// Change the cursor icon to a hand
     /// when hovering over the button.
     #[inline]
@@ -100,7 +112,7 @@ impl Widget for Button {
 
         let id = match &self.content {
             Content::Text(text) => ctx.create_id(text),
-            Content::Icon(icon) => ctx.create_id(&(*icon as u8 as *const u8))
+            Content::Icon(icon) => ctx.create_id(icon)
         };
 
         let rect = ctx.layout_next();
@@ -109,11 +121,11 @@ impl Widget for Button {
                 .cursor(crate::CursorIcon::Hand)
         } else {
             WidgetInteraction::from(self.options)
-        };
+        }.focusable();
 
         ctx.update_widget(id, rect, interaction);
 
-        if ctx.mouse_pressed(MouseButton::Left) && ctx.is_focused(id) {
+        if (ctx.mouse_pressed(MouseButton::Left) && ctx.is_focused(id)) || ctx.key_activated(id) {
             resp.submit = true;
         }
 
@@ -121,7 +133,8 @@ impl Widget for Button {
 
         match self.content {
             Content::Text(text) => {
-                ctx.draw_widget_text(text, rect, WidgetColor::Text, self.options);
+                let font = self.font.unwrap_or(ctx.style.font);
+                ctx.draw_widget_text_with(font, text, rect, WidgetColor::Text, self.options);
             },
             Content::Icon(icon) => {
                 if !matches!(icon, Icon::None) {
@@ -29,9 +29,9 @@ impl<'a> Widget for Checkbox<'a> {
         let r = ctx.layout_next();
         let frame = rect(r.x, r.y, r.h, r.h);
 
-        ctx.update_widget(id, r, WidgetInteraction::default());
+        ctx.update_widget(id, r, WidgetInteraction::default().focusable());
 
-        if ctx.mouse_pressed.is_set(MouseButton::Left) && ctx.is_hovered(id) {
+        if (ctx.mouse_pressed.is_set(MouseButton::Left) && ctx.is_hovered(id)) || ctx.key_activated(id) {
             resp.change = true;
             *self.checked = !*self.checked;
         }
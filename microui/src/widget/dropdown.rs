@@ -2,7 +2,7 @@ use std::{cmp, borrow::Cow};
 
 use crate::{
     Context, ContainerOptions, ContainerOption, WidgetInteraction,
-    MouseButton, Response, WidgetColor, Vec2, rect
+    MouseButton, Key, Response, WidgetColor, Vec2, rect
 };
 use super::{Widget, HorizontalAlign, Button};
 
@@ -103,6 +103,7 @@ impl<'a, T: AsRef<str>> Widget for Dropdown<'a, T> {
         };
 
         let btn_resp = self.body.text(label).draw(ctx);
+        let btn_id = ctx.last_id;
         let mut resp = Response::default();
 
         if btn_resp.submit {
@@ -111,6 +112,15 @@ impl<'a, T: AsRef<str>> Widget for Dropdown<'a, T> {
             resp.change = true;
         }
 
+        // When opened with the keyboard, the selected entry is focused instead
+        // of the button. The key is consumed so it doesn't also pick the entry.
+        let focus_entry = btn_resp.submit && self.state.is_open && ctx.key_focus;
+
+        if focus_entry {
+            ctx.consume_key(Key::Return);
+            ctx.consume_key(Key::Space);
+        }
+
         if !self.state.is_open {
             return resp;
         }
@@ -153,8 +163,12 @@ impl<'a, T: AsRef<str>> Widget for Dropdown<'a, T> {
                 let spacing = ctx.style.spacing;
                 ctx.style.spacing = 0;
     
+                let focused = self.state.index.unwrap_or(0);
+
                 for (i, option) in self.items.iter().enumerate() {
-                    if dropdown_entry(ctx, i, option.as_ref(), self.content_options) {
+                    let focus = focus_entry && i == focused;
+
+                    if dropdown_entry(ctx, i, option.as_ref(), self.content_options, focus) {
                         self.state.index = Some(i);
                         resp.submit = true;
                     }
@@ -170,6 +184,12 @@ impl<'a, T: AsRef<str>> Widget for Dropdown<'a, T> {
                 ctx.containers[cnt_idx].open = false;
                 self.state.toggle();
 
+                // Give the focus back to the button if it was in the dropdown.
+                if ctx.key_focus {
+                    ctx.set_focus(btn_id);
+                    ctx.key_focus = true;
+                }
+
                 resp.change = true;
                 resp.active = false;
             }
@@ -194,14 +214,25 @@ impl State {
     }
 }
 
-fn dropdown_entry(ctx: &mut Context, index: usize, text: &str, options: ContainerOptions) -> bool {
+fn dropdown_entry(
+    ctx: &mut Context,
+    index: usize,
+    text: &str,
+    options: ContainerOptions,
+    focus: bool
+) -> bool {
     let mut resp = false;
     let id = ctx.create_id(&[text.as_ptr() as usize, index]);
 
+    if focus {
+        ctx.set_focus(Some(id));
+        ctx.key_focus = true;
+    }
+
     let rect = ctx.layout_next();
-    ctx.update_widget(id, rect, WidgetInteraction::default());
+    ctx.update_widget(id, rect, WidgetInteraction::default().focusable());
 
-    if ctx.mouse_pressed(MouseButton::Left) && ctx.is_focused(id) {
+    if (ctx.mouse_pressed(MouseButton::Left) && ctx.is_focused(id)) || ctx.key_activated(id) {
         resp = true;
     }
 
@@ -216,3 +247,53 @@ fn dropdown_entry(ctx: &mut Context, index: usize, text: &str, options: Containe
 
     resp
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Window, Id, rect};
+    use super::*;
+    use crate::test_util::Handler;
+
+    const ITEMS: [&str; 3] = ["One", "Two", "Three"];
+
+    /// Returns the id of the dropdown button.
+    fn frame(ctx: &mut Context, state: &mut State, keys: &[Key]) -> Id {
+        for key in keys {
+            ctx.input_key_down(*key);
+        }
+
+        let mut id = None;
+
+        ctx.begin();
+        Window::new("Dropdown", rect(0, 0, 200, 200)).show(ctx, |ctx| {
+            Dropdown::new(state, &ITEMS).draw(ctx);
+            id = ctx.last_id;
+        });
+        ctx.end();
+
+        for key in keys {
+            ctx.input_key_up(*key);
+        }
+
+        id.unwrap()
+    }
+
+    #[test]
+    fn keyboard() {
+        let mut ctx = Context::new(Handler);
+        let mut state = State::with_selection(0);
+
+        frame(&mut ctx, &mut state, &[]);
+        let button = frame(&mut ctx, &mut state, &[Key::Tab]);
+        assert!(ctx.is_focused(button));
+
+        // Opening moves the focus to the selected entry without picking it.
+        frame(&mut ctx, &mut state, &[Key::Return]);
+        assert!(state.is_open && !ctx.is_focused(button));
+
+        frame(&mut ctx, &mut state, &[Key::Tab]);
+        frame(&mut ctx, &mut state, &[Key::Return]);
+        assert_eq!(state, State::with_selection(1));
+        assert!(ctx.is_focused(button));
+    }
+}
@@ -0,0 +1,193 @@
+use std::hash::Hash;
+
+use crate::{
+    Context, ContainerOptions, ContainerOption, MouseButton, ImageId, UvRect,
+    Color, CursorIcon, WidgetInteraction, WidgetColor, Response, Id
+};
+use super::Widget;
+
+/// Draws an image stretched over the next rect of the layout.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Image {
+    id: ImageId,
+    uv: UvRect,
+    tint: Color
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ImageButton {
+    id: ImageId,
+    id_source: Option<Id>,
+    uv: UvRect,
+    tint: Color,
+    options: ContainerOptions,
+    hand_cursor: bool
+}
+
+impl Image {
+    #[inline]
+    pub fn new(id: ImageId) -> Self {
+        Self {
+            id,
+            uv: UvRect::FULL,
+            tint: Color::rgb(255, 255, 255)
+        }
+    }
+
+    /// Only draw a part of the image, i.e. one from a sprite sheet.
+    #[inline]
+    pub fn uv(mut self, uv: UvRect) -> Self {
+        self.uv = uv;
+
+        self
+    }
+
+    /// Multiply the colors of the image with `tint`.
+    #[inline]
+    pub fn tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+
+        self
+    }
+}
+
+impl Widget for Image {
+    #[inline]
+    fn draw(self, ctx: &mut Context) -> Response {
+        let rect = ctx.layout_next();
+        ctx.draw_image(self.id, rect, self.uv, self.tint);
+
+        Response::default()
+    }
+}
+
+impl ImageButton {
+    #[inline]
+    pub fn new(id: ImageId) -> Self {
+        Self {
+            id,
+            id_source: None,
+            uv: UvRect::FULL,
+            tint: Color::rgb(255, 255, 255),
+            options: ContainerOptions::default(),
+            hand_cursor: false
+        }
+    }
+
+    /// Only draw a part of the image, i.e. one from a sprite sheet.
+    #[inline]
+    pub fn uv(mut self, uv: UvRect) -> Self {
+        self.uv = uv;
+
+        self
+    }
+
+    /// Multiply the colors of the image with `tint`.
+    #[inline]
+    pub fn tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+
+        self
+    }
+
+    /// Buttons are identified by their image, like buttons by their text. Buttons
+    /// showing the same image in the same container need a different `source` each.
+    #[inline]
+    pub fn id_source(mut self, source: &impl Hash) -> Self {
+        self.id_source = Some(Id::new(source, 0));
+
+        self
+    }
+
+    /// Change the cursor icon to a hand
+    /// when hovering over the button.
+    #[inline]
+    pub fn with_cursor(mut self) -> Self {
+        self.hand_cursor = true;
+
+        self
+    }
+
+    #[inline]
+    pub fn no_frame(mut self) -> Self {
+        self.options.set(ContainerOption::NoFrame);
+
+        self
+    }
+}
+
+impl Widget for ImageButton {
+    fn draw(self, ctx: &mut Context) -> Response {
+        let mut resp = Response::default();
+
+        let id = match self.id_source {
+            Some(source) => ctx.create_id(&("!image", source.0)),
+            None => ctx.create_id(&("!image", self.id))
+        };
+        let rect = ctx.layout_next();
+
+        let interaction = if self.hand_cursor {
+            WidgetInteraction::from(self.options).cursor(CursorIcon::Hand)
+        } else {
+            WidgetInteraction::from(self.options)
+        }.focusable();
+
+        ctx.update_widget(id, rect, interaction);
+
+        if (ctx.mouse_pressed(MouseButton::Left) && ctx.is_focused(id)) || ctx.key_activated(id) {
+            resp.submit = true;
+        }
+
+        ctx.draw_widget_frame(id, rect, WidgetColor::Button, self.options);
+        ctx.draw_image(self.id, rect.expand(-ctx.px(ctx.style.padding as i32)), self.uv, self.tint);
+
+        resp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Window, DrawCommand, rect, vec2};
+    use crate::test_util::Handler;
+
+    #[test]
+    fn image_button() {
+        let mut ctx = Context::new(Handler);
+        let mut clicked = false;
+        let mut other = false;
+
+        let mut frame = |ctx: &mut Context| {
+            ctx.begin();
+            Window::new("Images", rect(0, 0, 200, 200)).show(ctx, |ctx| {
+                ctx.layout_row(&[32, 32, 32], 32);
+                ctx.image(ImageId(1));
+                clicked |= ctx.image_button(ImageId(2));
+                other |= ImageButton::new(ImageId(2)).id_source(&"other").draw(ctx).submit;
+            });
+            ctx.end();
+
+            ctx.commands()
+                .filter_map(|cmd| match cmd {
+                    DrawCommand::Image { id, rect, .. } => Some((id, rect)),
+                    _ => None
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let images = frame(&mut ctx);
+        assert_eq!(images[0].0, ImageId(1));
+        assert_eq!(images[1].0, ImageId(2));
+        assert_eq!(images[0].1.w, 32);
+        assert_eq!(images[1].1.w, 32 - ctx.style.padding as i32 * 2);
+
+        let button = images[1].1;
+        let pos = vec2(button.x + 4, button.y + 4);
+        ctx.input_mouse_move(pos);
+        frame(&mut ctx);
+        ctx.input_mouse_down(pos, MouseButton::Left);
+        frame(&mut ctx);
+
+        assert!(clicked && !other);
+    }
+}
@@ -1,18 +1,20 @@
 use crate::{
     Context, Response, ContainerOptions, MouseButton,
-    WidgetColor, WidgetInteraction, CursorIcon, rect
+    WidgetColor, WidgetInteraction, CursorIcon, FontId, rect
 };
 use super::{Widget, HorizontalAlign};
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct Label {
     text: String,
+    font: Option<FontId>,
     options: ContainerOptions
 }
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct ClickableLabel {
     text: String,
+    font: Option<FontId>,
     options: ContainerOptions
 }
 
@@ -21,10 +23,19 @@ impl Label {
     pub fn new(text: impl Into<String>) -> Self {
         Self {
             text: text.into(),
+            font: None,
             options: ContainerOptions::default()
         }
     }
 
+    /// Draws the text with `font` instead of the style's font.
+    #[inline]
+    pub fn font(mut self, font: FontId) -> Self {
+        self.font = Some(font);
+
+        self
+    }
+
     #[inline]
     pub fn align(mut self, align: HorizontalAlign) -> Self {
         if let Some(option) = align.into() {
@@ -39,7 +50,8 @@ impl Widget for Label {
     #[inline]
     fn draw(self, ctx: &mut Context) -> Response {
         let layout = ctx.layout_next();
-        ctx.draw_widget_text(
+        ctx.draw_widget_text_with(
+            self.font.unwrap_or(ctx.style.font),
             self.text,
             layout,
             WidgetColor::Text,
@@ -55,10 +67,19 @@ impl ClickableLabel {
     pub fn new(text: impl Into<String>) -> Self {
         Self {
             text: text.into(),
+            font: None,
             options: ContainerOptions::default()
         }
     }
 
+    /// Draws the text with `font` instead of the style's font.
+    #[inline]
+    pub fn font(mut self, font: FontId) -> Self {
+        self.font = Some(font);
+
+        self
+    }
+
     #[inline]
     pub fn align(mut self, align: HorizontalAlign) -> Self {
         if let Some(option) = align.into() {
@@ -79,9 +100,11 @@ impl Widget for ClickableLabel {
             layout,
             WidgetInteraction::from(self.options)
                 .cursor(CursorIcon::Hand)
+                .focusable()
         );
 
-        let text_rect = ctx.draw_widget_text(
+        let text_rect = ctx.draw_widget_text_with(
+            self.font.unwrap_or(ctx.style.font),
             self.text,
             layout,
             WidgetColor::Text,
@@ -90,13 +113,13 @@ impl Widget for ClickableLabel {
 
         if ctx.is_hovered(id) {
             ctx.draw_rect(
-                rect(text_rect.x, text_rect.y + text_rect.h, text_rect.w, 1),
+                rect(text_rect.x, text_rect.y + text_rect.h, text_rect.w, ctx.px_line(1)),
                 ctx.style.colors[WidgetColor::Text]
             );
         }
 
         let mut resp = Response::default();
-        if ctx.mouse_pressed(MouseButton::Left) && ctx.is_focused(id) {
+        if (ctx.mouse_pressed(MouseButton::Left) && ctx.is_focused(id)) || ctx.key_activated(id) {
             resp.submit = true;
         }
 
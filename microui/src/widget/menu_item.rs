@@ -0,0 +1,121 @@
+use std::cmp;
+
+use crate::{
+    Context, ContainerOptions, MouseButton, Icon,
+    WidgetInteraction, WidgetColor, Response, Rect, TextSizeHandler, rect
+};
+use super::Widget;
+
+/// An entry of a [`ContextMenu`](crate::ContextMenu) or [`Submenu`](crate::Submenu).
+/// Clicking it closes the menus it's in.
+pub struct MenuItem<'a> {
+    text: String,
+    checked: Option<&'a mut bool>,
+    enabled: bool
+}
+
+impl<'a> MenuItem<'a> {
+    #[inline]
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            checked: None,
+            enabled: true
+        }
+    }
+
+    /// Shows a check mark in front of the item while `checked` is `true`
+    /// and toggles it when the item is clicked.
+    #[inline]
+    pub fn checkable(mut self, checked: &'a mut bool) -> Self {
+        self.checked = Some(checked);
+
+        self
+    }
+
+    /// Disabled items are grayed out and can't be clicked.
+    #[inline]
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+
+        self
+    }
+}
+
+impl<'a> Widget for MenuItem<'a> {
+    fn draw(mut self, ctx: &mut Context) -> Response {
+        let mut resp = Response::default();
+
+        let id = ctx.create_id(&self.text);
+        let r = menu_row(ctx, &self.text);
+
+        let interaction = if self.enabled {
+            WidgetInteraction::default().focusable()
+        } else {
+            WidgetInteraction::default().no_interact()
+        };
+
+        ctx.update_widget(id, r, interaction);
+
+        if (ctx.mouse_pressed(MouseButton::Left) && ctx.is_focused(id)) || ctx.key_activated(id) {
+            resp.submit = true;
+            ctx.close_menus = true;
+
+            if let Some(checked) = &mut self.checked {
+                **checked = !**checked;
+                resp.change = true;
+            }
+        }
+
+        draw_menu_row(ctx, r, &self.text, ctx.is_hovered(id), self.enabled);
+
+        if let Some(true) = self.checked.as_deref() {
+            ctx.draw_icon(Icon::Check, rect(r.x, r.y, r.h, r.h), ctx.style.colors[WidgetColor::Text]);
+        }
+
+        resp
+    }
+}
+
+impl Context {
+    /// A line between groups of menu items.
+    pub fn menu_separator(&mut self) {
+        let gap = self.px(self.style.padding as i32);
+        let line = self.px_line(1);
+
+        self.layout_mut().row(&[-1], gap * 2 + line);
+        let r = self.layout_next();
+
+        self.draw_rect(rect(r.x, r.y + gap, r.w, line), self.style.colors[WidgetColor::Border]);
+    }
+}
+
+/// Lays out a row for a menu entry. Entries fill the width of the menu, which
+/// grows to fit the widest one. Room is left for a check mark on the left and
+/// the arrow of a submenu on the right.
+pub(crate) fn menu_row(ctx: &mut Context, text: &str) -> Rect {
+    let padding = ctx.px(ctx.style.padding as i32);
+    let height = ctx.px(ctx.style.size.y) + padding * 2;
+    let width = ctx.font_handler.text_width(ctx.style.font, text) + padding * 2 + height * 2;
+
+    let layout = ctx.layout_mut();
+    let width = cmp::max(width, layout.body.w);
+    layout.row(&[width], height);
+
+    ctx.layout_next()
+}
+
+pub(crate) fn draw_menu_row(ctx: &mut Context, r: Rect, text: &str, highlight: bool, enabled: bool) {
+    if highlight {
+        ctx.draw_rect(r, ctx.style.colors[WidgetColor::ButtonHover]);
+    }
+
+    let color = if enabled {
+        WidgetColor::Text
+    } else {
+        WidgetColor::TextDisabled
+    };
+
+    let text_rect = rect(r.x + r.h, r.y, r.w - r.h * 2, r.h);
+    ctx.draw_widget_text(text, text_rect, color, ContainerOptions::default());
+}
@@ -6,14 +6,20 @@ mod label;
 mod checkbox;
 mod slider;
 mod drag_value;
+mod text_area;
+mod image;
+mod menu_item;
 
 pub use button::*;
 pub use label::*;
 pub use checkbox::*;
 pub use textbox::TextBox;
+pub use text_area::*;
 pub use slider::*;
 pub use drag_value::*;
 pub use dropdown::*;
+pub use image::*;
+pub use menu_item::*;
 
 use crate::{Context, Response, ContainerOption};
 
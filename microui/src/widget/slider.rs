@@ -90,7 +90,7 @@ impl<'a> Widget for Slider<'a> {
 
         ctx.draw_widget_frame(id, base, WidgetColor::Base, self.options);
 
-        let w = ctx.style.thumb_size as i32;
+        let w = ctx.px(ctx.style.thumb_size as i32);
         let x = ((v - self.range.start) * (base.w - w) as f64 / (self.range.end - self.range.start)) as i32;
 
         let thumb = rect(base.x + x, base.y, w, base.h);
@@ -0,0 +1,333 @@
+use std::{cmp, ops::Range};
+
+use crate::{
+    Context, ContainerOptions, ContainerOption, MouseButton, CursorIcon,
+    WidgetInteraction, Key, Response, WidgetColor, TextBuf, TextSizeHandler, FontId, text, vec2, rect
+};
+use super::{Widget, textbox::{self, EditState}};
+
+/// A multi-line text editor. Lines are wrapped the same way as in
+/// [`Context::text`] and the text scrolls inside of the widget.
+pub struct TextArea<'a, T: TextBuf> {
+    buf: &'a mut T,
+    options: ContainerOptions
+}
+
+impl<'a, T: TextBuf> TextArea<'a, T> {
+    #[inline]
+    pub fn new(buf: &'a mut T) -> Self {
+        Self {
+            buf,
+            options: ContainerOptions::default()
+        }
+    }
+
+    #[inline]
+    pub fn no_frame(mut self) -> Self {
+        self.options.set(ContainerOption::NoFrame);
+
+        self
+    }
+}
+
+impl<'a, T: TextBuf> Widget for TextArea<'a, T> {
+    fn draw(self, ctx: &mut Context) -> Response {
+        let mut resp = Response::default();
+        let r = ctx.layout_next();
+
+        // Use the address of the buffer itself rather than its contents
+        // because a growing String moves its contents around.
+        let id = ctx.push_id(&(self.buf as *const T));
+
+        let cnt_idx = match ctx.get_container(id, ContainerOptions::default()) {
+            Some(index) => index,
+            None => {
+                ctx.pop_id();
+
+                return resp;
+            }
+        };
+
+        ctx.containers[cnt_idx].rect = r;
+
+        let mut opts_copy = self.options;
+        opts_copy.set(ContainerOption::HoldFocus);
+
+        ctx.update_widget(
+            id,
+            r,
+            WidgetInteraction::from(opts_copy)
+                .cursor(CursorIcon::Text)
+                .focusable()
+        );
+        ctx.draw_widget_frame(id, r, WidgetColor::Base, self.options);
+
+        ctx.container_stack.push(cnt_idx);
+        ctx.push_container_body(cnt_idx, r, ContainerOptions::default());
+
+        let body = ctx.containers[cnt_idx].body;
+        ctx.push_clip_rect(body);
+
+        let font = ctx.style.font;
+        let line_height = ctx.font_handler.text_height(font);
+        let line_step = line_height + ctx.px(ctx.style.spacing as i32);
+
+        // The area the lines are laid out in, offset by the scroll position.
+        let area = ctx.layout_mut().body;
+
+        if ctx.is_focused(id) {
+            let focus_gained = ctx.text_edit_id != Some(id);
+
+            if focus_gained {
+                ctx.text_edit_id = Some(id);
+                ctx.text_edit = EditState::default();
+                ctx.text_edit.move_to(self.buf.as_str().len(), false);
+            }
+
+            let prev_state = ctx.text_edit;
+            let mut state = prev_state;
+            state.clamp(self.buf.as_str());
+
+            let shift = ctx.key_down(Key::Shift);
+
+            // Place the caret under the mouse or extend the selection while dragging.
+            if ctx.mouse_down(MouseButton::Left) {
+                let text = self.buf.as_str();
+                let lines = wrap_lines(ctx, font, text, area.w);
+
+                let index = (ctx.mouse_pos.y - area.y)
+                    .div_euclid(line_step)
+                    .clamp(0, lines.len() as i32 - 1) as usize;
+                let pos = hit_line(ctx, font, text, &lines, index, ctx.mouse_pos.x - area.x);
+
+                if ctx.mouse_pressed(MouseButton::Left) {
+                    state.move_to(pos, shift && !focus_gained);
+                } else {
+                    state.move_to(pos, true);
+                }
+            }
+
+            if textbox::edit_keys(ctx, self.buf, &mut state, true) {
+                resp.change = true;
+            }
+
+            if ctx.key_pressed(Key::Escape) {
+                ctx.set_focus(None);
+                ctx.consume_key(Key::Escape);
+            }
+
+            if ctx.key_pressed(Key::Return) {
+                state.delete_selection(self.buf);
+
+                let count = self.buf.insert_str(state.cursor, "\n");
+                state.move_to(state.cursor + count, false);
+
+                resp.change = true;
+            }
+
+            let text = self.buf.as_str();
+            let lines = wrap_lines(ctx, font, text, area.w);
+            let line = line_at(&lines, state.cursor);
+
+            if ctx.key_pressed(Key::Home) {
+                state.move_to(lines[line].start, shift);
+            }
+
+            if ctx.key_pressed(Key::End) {
+                state.move_to(line_end(text, &lines, line), shift);
+            }
+
+            if ctx.key_pressed(Key::Up) || ctx.key_pressed(Key::Down) {
+                let x = ctx.font_handler.text_width(font, &text[lines[line].start..state.cursor]);
+
+                let pos = if ctx.key_pressed(Key::Up) {
+                    match line.checked_sub(1) {
+                        Some(index) => hit_line(ctx, font, text, &lines, index, x),
+                        None => 0
+                    }
+                } else if line + 1 < lines.len() {
+                    hit_line(ctx, font, text, &lines, line + 1, x)
+                } else {
+                    text.len()
+                };
+
+                state.move_to(pos, shift);
+            }
+
+            // Scroll vertically to keep the caret visible, but only when it
+            // moved so that the mouse wheel can still be used while editing.
+            if state != prev_state || resp.change {
+                let y = line_at(&lines, state.cursor) as i32 * line_step;
+                let visible = body.h - ctx.px(ctx.style.padding as i32) * 2;
+
+                let scroll = &mut ctx.containers[cnt_idx].scroll.y;
+                *scroll = cmp::min(cmp::max(*scroll, y + line_height - visible), y);
+            }
+
+            ctx.text_edit = state;
+        }
+
+        let text = self.buf.as_str();
+        let lines = wrap_lines(ctx, font, text, area.w);
+
+        let focused = ctx.is_focused(id);
+        let state = ctx.text_edit;
+        let caret_line = line_at(&lines, state.cursor);
+        let selection = state.selection();
+
+        let color = ctx.style.colors[WidgetColor::Text];
+        let selection_color = ctx.style.colors[WidgetColor::TextSelection];
+
+        ctx.layout_mut().row(&[-1], line_height);
+
+        for (i, line) in lines.iter().enumerate() {
+            let lr = ctx.layout_next();
+            let end = if text[line.clone()].ends_with('\n') { line.end - 1 } else { line.end };
+            let width = |ctx: &Context, pos: usize| {
+                ctx.font_handler.text_width(font, &text[line.start..cmp::min(pos, end)])
+            };
+
+            if focused && selection.start < line.end && selection.end > line.start {
+                let start = width(ctx, cmp::max(selection.start, line.start));
+                let end = width(ctx, selection.end);
+
+                ctx.draw_rect(rect(lr.x + start, lr.y, end - start, line_height), selection_color);
+            }
+
+            ctx.draw_text(font, &text[line.start..end], vec2(lr.x, lr.y), color);
+
+            if focused && i == caret_line {
+                let x = lr.x + width(ctx, state.cursor);
+                ctx.draw_rect(rect(x, lr.y, ctx.px_line(1), line_height), color);
+            }
+        }
+
+        ctx.pop_clip_rect();
+        ctx.pop_container();
+
+        resp
+    }
+}
+
+/// The byte ranges of the lines of `text` wrapped to `width`. There is always
+/// at least one line, with an empty one after a trailing line break.
+fn wrap_lines(ctx: &Context, font: FontId, text: &str, width: i32) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+
+    while start < text.len() {
+        let end = start + ctx.wrap_line(font, &text[start..], width);
+        lines.push(start..end);
+        start = end;
+    }
+
+    if text.is_empty() || text.ends_with('\n') {
+        lines.push(text.len()..text.len());
+    }
+
+    lines
+}
+
+/// The line the caret is drawn on when it's at `pos`.
+#[inline]
+fn line_at(lines: &[Range<usize>], pos: usize) -> usize {
+    lines.iter().rposition(|x| x.start <= pos).unwrap_or(0)
+}
+
+/// The last caret position on a line, which is before the
+/// line break or space the line was wrapped at. Lines that were
+/// broken inside of a word end where the next line starts.
+#[inline]
+fn line_end(text: &str, lines: &[Range<usize>], index: usize) -> usize {
+    let end = lines[index].end;
+
+    if index + 1 < lines.len() && text[..end].ends_with([' ', '\n']) {
+        text::prev_grapheme(text, end)
+    } else {
+        end
+    }
+}
+
+/// Returns the grapheme boundary on line `index` closest to `x` pixels from the start of the line.
+fn hit_line(
+    ctx: &Context,
+    font: FontId,
+    text: &str,
+    lines: &[Range<usize>],
+    index: usize,
+    x: i32
+) -> usize {
+    let start = lines[index].start;
+    let end = line_end(text, lines, index);
+
+    start + textbox::hit_test(&ctx.font_handler, font, &text[start..end], x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Window, const_vec::ConstStr};
+    use crate::test_util::Handler;
+
+    fn frame(ctx: &mut Context, buf: &mut ConstStr<64>) -> Option<EditState> {
+        let mut state = None;
+
+        ctx.begin();
+        Window::new("Test", rect(0, 0, 300, 200)).show(ctx, |ctx| {
+            ctx.layout_row(&[-1], 100);
+            ctx.text_area(buf);
+            state = ctx.text_edit_state(ctx.last_id().unwrap());
+        });
+        ctx.end();
+
+        state
+    }
+
+    fn press(ctx: &mut Context, buf: &mut ConstStr<64>, key: Key) -> usize {
+        ctx.input_key_down(key);
+        let state = frame(ctx, buf).unwrap();
+        ctx.input_key_up(key);
+
+        state.cursor
+    }
+
+    #[test]
+    fn wrapping() {
+        let ctx = Context::new(Handler);
+        let text = "one two three\nfour\n";
+        let lines = wrap_lines(&ctx, FontId::default(), text, 8 * 8);
+
+        assert_eq!(lines, [0..8, 8..14, 14..19, 19..19]);
+        assert_eq!(line_at(&lines, 8), 1);
+        assert_eq!(line_end(text, &lines, 0), 7);
+        assert_eq!(line_end(text, &lines, 3), 19);
+        assert_eq!(wrap_lines(&ctx, FontId::default(), "", 10), vec![Range { start: 0, end: 0 }]);
+    }
+
+    #[test]
+    fn editing() {
+        let mut ctx = Context::new(Handler);
+        let mut buf = ConstStr::<64>::new();
+
+        ctx.input_mouse_move(vec2(50, 60));
+        frame(&mut ctx, &mut buf);
+        frame(&mut ctx, &mut buf);
+        ctx.input_mouse_down(vec2(50, 60), MouseButton::Left);
+        frame(&mut ctx, &mut buf).unwrap();
+        ctx.input_mouse_up(vec2(50, 60), MouseButton::Left);
+
+        ctx.input_text("abc");
+        frame(&mut ctx, &mut buf);
+        press(&mut ctx, &mut buf, Key::Return);
+        ctx.input_text("de");
+        frame(&mut ctx, &mut buf);
+        assert_eq!(buf.as_str(), "abc\nde");
+
+        assert_eq!(press(&mut ctx, &mut buf, Key::Up), 2);
+        assert_eq!(press(&mut ctx, &mut buf, Key::End), 3);
+        assert_eq!(press(&mut ctx, &mut buf, Key::Down), 6);
+        assert_eq!(press(&mut ctx, &mut buf, Key::Home), 4);
+        assert_eq!(press(&mut ctx, &mut buf, Key::Up), 0);
+        assert_eq!(press(&mut ctx, &mut buf, Key::Up), 0);
+    }
+}
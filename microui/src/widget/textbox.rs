@@ -1,9 +1,10 @@
-use std::{cmp, fmt::Write};
+use std::{cmp, mem, ops::Range, fmt::Write};
 
 use crate::{
     Context, ContainerOptions, ContainerOption, MouseButton,
-    CursorIcon, WidgetInteraction, ModKey, Id, Rect, Response,
-    WidgetColor, TextBuf, vec2, rect
+    CursorIcon, WidgetInteraction, Key, Id, Rect, Response,
+    WidgetColor, TextBuf, TextSizeHandler, FontId, vec2, rect,
+    const_vec::ConstStr, text::{prev_grapheme, next_grapheme, grapheme_boundaries}
 };
 use super::Widget;
 
@@ -17,6 +18,15 @@ pub struct TextBox<'a, T: TextBuf> {
     options: ContainerOptions
 }
 
+/// The caret and selection of the text box that is currently being edited.
+/// Both positions are byte indices into the text.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct EditState {
+    pub cursor: usize,
+    pub anchor: usize,
+    scroll: i32
+}
+
 impl<'a, T: TextBuf> TextBox<'a , T> {
     #[inline]
     pub fn new(buf: &'a mut T) -> Self {
@@ -41,67 +51,141 @@ pub fn raw(
     r: Rect,
     options: ContainerOptions
 ) -> Response {
-    let mut resp = Response::default();
-
     let mut opts_copy = options;
     opts_copy.set(ContainerOption::HoldFocus);
 
-    ctx.update_widget(
-        id,
-        r,
-        WidgetInteraction::from(opts_copy).cursor(CursorIcon::Text)
-    );
-
-    let text: String = if ctx.is_focused(id) {
-        // Handle text input
-        let input = ctx.text_input.as_str();
-        let buf = match buf {
-            TextBoxBuf::Text(buf) => buf,
-            TextBoxBuf::Numeric => &mut ctx.number_edit_buf as &mut dyn TextBuf
-        };
+    let interaction = WidgetInteraction::from(opts_copy).cursor(CursorIcon::Text);
 
-        if buf.push_str(input) > 0 {
-            resp.change = true;
+    // The number text box only exists while editing so it isn't a separate focus stop.
+    if let TextBoxBuf::Text(_) = buf {
+        ctx.update_widget(id, r, interaction.focusable());
+    } else {
+        ctx.update_widget(id, r, interaction);
+    }
+
+    match buf {
+        TextBoxBuf::Text(buf) => edit(ctx, buf, id, r, options, false),
+        TextBoxBuf::Numeric => {
+            // Move the buffer out so that it can be edited alongside the context.
+            let mut buf = mem::replace(&mut ctx.number_edit_buf, ConstStr::new());
+            let resp = edit(ctx, &mut buf, id, r, options, true);
+            ctx.number_edit_buf = buf;
+
+            resp
+        }
+    }
+}
+
+fn edit(
+    ctx: &mut Context,
+    buf: &mut dyn TextBuf,
+    id: Id,
+    r: Rect,
+    options: ContainerOptions,
+    select_all: bool
+) -> Response {
+    let mut resp = Response::default();
+
+    let font = ctx.style.font;
+    let padding = ctx.px(ctx.style.padding as i32);
+
+    if ctx.is_focused(id) {
+        let len = buf.as_str().len();
+        let focus_gained = ctx.text_edit_id != Some(id);
+
+        if focus_gained {
+            ctx.text_edit_id = Some(id);
+            ctx.text_edit = EditState {
+                cursor: len,
+                anchor: if select_all { 0 } else { len },
+                scroll: 0
+            };
+        }
+
+        let mut state = ctx.text_edit;
+        state.clamp(buf.as_str());
+
+        // Place the caret under the mouse or extend the selection while dragging.
+        if ctx.mouse_down(MouseButton::Left) && !(focus_gained && select_all) {
+            let x = ctx.mouse_pos.x - (r.x + padding - state.scroll);
+            let pos = hit_test(&ctx.font_handler, font, buf.as_str(), x);
+
+            if ctx.mouse_pressed(MouseButton::Left) {
+                let extend = ctx.key_down(Key::Shift) && !focus_gained;
+                state.move_to(pos, extend);
+            } else {
+                state.move_to(pos, true);
+            }
         }
 
-        if ctx.key_pressed.is_set(ModKey::Backspace) {
-            buf.pop_char();
+        let shift = ctx.key_down(Key::Shift);
+
+        if edit_keys(ctx, buf, &mut state, false) {
             resp.change = true;
         }
 
-        let text = buf.as_str().into();
+        let text = buf.as_str();
+
+        if ctx.key_pressed(Key::Home) {
+            state.move_to(0, shift);
+        }
+
+        if ctx.key_pressed(Key::End) {
+            state.move_to(text.len(), shift);
+        }
+
+        // Scroll horizontally to keep the caret visible.
+        let textw = ctx.font_handler.text_width(font, text);
+        let caretx = ctx.font_handler.text_width(font, &text[..state.cursor]);
+        let visible = cmp::max(r.w - padding * 2 - ctx.px_line(1), 0);
+
+        state.scroll = state.scroll
+            .min(cmp::max(textw - visible, 0))
+            .clamp(caretx - visible, caretx)
+            .max(0);
+
+        ctx.text_edit = state;
 
-        if ctx.key_pressed.is_set(ModKey::Return) {
+        if ctx.key_pressed(Key::Return) {
             ctx.set_focus(None);
             resp.submit = true;
+        } else if ctx.key_pressed(Key::Escape) {
+            ctx.set_focus(None);
+            ctx.consume_key(Key::Escape);
         }
+    }
 
-        text
-    } else {
-        let buf = match buf {
-            TextBoxBuf::Text(buf) => buf,
-            TextBoxBuf::Numeric => &mut ctx.number_edit_buf as &mut dyn TextBuf
-        };
-
-        buf.as_str().into()
-    };
+    let text: String = buf.as_str().into();
 
     ctx.draw_widget_frame(id, r, WidgetColor::Base, options);
 
     if ctx.is_focused(id) {
+        let state = ctx.text_edit;
         let color = ctx.style.colors[WidgetColor::Text];
 
-        let font = ctx.style.font;
-        let textw = ctx.font_handler.text_width(font, &text);
         let texth = ctx.font_handler.text_height(font);
-
-        let offset = r.w - ctx.style.padding as i32 - textw - 1;
-        let textx = r.x + cmp::min(offset, ctx.style.padding as i32);
+        let textx = r.x + padding - state.scroll;
         let texty = r.y + (r.h - texth) / 2;
 
+        let width = |ctx: &Context, end: usize| ctx.font_handler.text_width(font, &text[..end]);
+
         ctx.push_clip_rect(r);
-        ctx.draw_text(font, text, vec2(textx, texty), color);
-        ctx.draw_rect(rect(textx + textw, texty, 1, texth), color);
+
+        if state.has_selection() {
+            let selection = state.selection();
+            let start = width(ctx, selection.start);
+            let end = width(ctx, selection.end);
+
+            ctx.draw_rect(
+                rect(textx + start, texty, end - start, texth),
+                ctx.style.colors[WidgetColor::TextSelection]
+            );
+        }
+
+        let caretx = textx + width(ctx, state.cursor);
+
+        ctx.draw_text(font, &text, vec2(textx, texty), color);
+        ctx.draw_rect(rect(caretx, texty, ctx.px_line(1), texth), color);
         ctx.pop_clip_rect();
     } else {
         ctx.draw_widget_text(text, r, WidgetColor::Text, options);
@@ -117,7 +201,7 @@ pub fn number(
     id: Id
 ) -> bool {
     if ctx.mouse_pressed.is_set(MouseButton::Left) &&
-        ctx.key_down.is_set(ModKey::Shift) &&
+        ctx.key_down.is_set(Key::Shift) &&
         ctx.is_hovered(id)
     {
         ctx.number_edit_id = Some(id);
@@ -131,6 +215,9 @@ pub fn number(
     }
 
     if ctx.number_edit_id.map_or(false, |x| x == id) {
+        // Escape drops the edited text and keeps the original value.
+        let cancel = ctx.is_focused(id) && ctx.key_pressed(Key::Escape);
+
         let resp = raw(
             ctx,
             TextBoxBuf::Numeric,
@@ -139,7 +226,9 @@ pub fn number(
             ContainerOptions::default()
         );
 
-        if resp.submit || !ctx.is_focused(id) {
+        if cancel {
+            ctx.number_edit_id = None;
+        } else if resp.submit || !ctx.is_focused(id) {
             if let Ok(val) = ctx.number_edit_buf.as_str().parse::<f64>() {
                 *value = val;
             }
@@ -153,6 +242,110 @@ pub fn number(
     false
 }
 
+/// Handles the text input, deletion, clipboard and horizontal movement keys
+/// shared by all text widgets. Returns `true` if the text was changed.
+pub(crate) fn edit_keys(
+    ctx: &mut Context,
+    buf: &mut dyn TextBuf,
+    state: &mut EditState,
+    multiline: bool
+) -> bool {
+    let mut change = false;
+
+    let ctrl = ctx.key_down(Key::Ctrl);
+    let shift = ctx.key_down(Key::Shift);
+
+    // Handle text input
+    let input = ctx.text_input.as_str();
+
+    if !input.is_empty() {
+        state.delete_selection(buf);
+
+        let count = buf.insert_str(state.cursor, input);
+        state.move_to(state.cursor + count, false);
+
+        change = true;
+    }
+
+    if ctx.key_pressed(Key::Backspace) && !state.delete_selection(buf) {
+        let text = buf.as_str();
+        let start = if ctrl {
+            prev_word(text, state.cursor)
+        } else {
+            prev_grapheme(text, state.cursor)
+        };
+
+        buf.remove(start..state.cursor);
+        state.move_to(start, false);
+    }
+
+    if ctx.key_pressed(Key::Delete) && !state.delete_selection(buf) {
+        let text = buf.as_str();
+        let end = if ctrl {
+            next_word(text, state.cursor)
+        } else {
+            next_grapheme(text, state.cursor)
+        };
+
+        buf.remove(state.cursor..end);
+    }
+
+    if ctx.key_pressed(Key::Backspace) || ctx.key_pressed(Key::Delete) {
+        change = true;
+    }
+
+    if ctrl && (ctx.key_pressed(Key::C) || ctx.key_pressed(Key::X)) && state.has_selection() {
+        ctx.clipboard.set(&buf.as_str()[state.selection()]);
+
+        if ctx.key_pressed(Key::X) {
+            state.delete_selection(buf);
+            change = true;
+        }
+    }
+
+    if ctrl && ctx.key_pressed(Key::V) {
+        if let Some(mut text) = ctx.clipboard.get() {
+            if multiline {
+                text.retain(|c| c != '\r');
+            } else {
+                // Line breaks can't be displayed in a single line text box.
+                text.retain(|c| !matches!(c, '\n' | '\r'));
+            }
+
+            state.delete_selection(buf);
+
+            let count = buf.insert_str(state.cursor, &text);
+            state.move_to(state.cursor + count, false);
+
+            change = true;
+        }
+    }
+
+    let text = buf.as_str();
+
+    if ctx.key_pressed(Key::Left) {
+        if state.has_selection() && !shift {
+            state.move_to(state.selection().start, false);
+        } else if ctrl {
+            state.move_to(prev_word(text, state.cursor), shift);
+        } else {
+            state.move_to(prev_grapheme(text, state.cursor), shift);
+        }
+    }
+
+    if ctx.key_pressed(Key::Right) {
+        if state.has_selection() && !shift {
+            state.move_to(state.selection().end, false);
+        } else if ctrl {
+            state.move_to(next_word(text, state.cursor), shift);
+        } else {
+            state.move_to(next_grapheme(text, state.cursor), shift);
+        }
+    }
+
+    change
+}
+
 impl<'a, T: TextBuf> Widget for TextBox<'a, T> {
     #[inline]
     fn draw(self, ctx: &mut Context) -> Response {
@@ -162,3 +355,235 @@ impl<'a, T: TextBuf> Widget for TextBox<'a, T> {
         raw(ctx, TextBoxBuf::Text(self.buf), id, rect, self.options)
     }
 }
+
+impl EditState {
+    /// The selected byte range, which is empty if nothing is selected.
+    #[inline]
+    pub fn selection(&self) -> Range<usize> {
+        cmp::min(self.cursor, self.anchor)..cmp::max(self.cursor, self.anchor)
+    }
+
+    #[inline]
+    pub fn has_selection(&self) -> bool {
+        self.cursor != self.anchor
+    }
+
+    /// Moves the caret to `pos`, keeping the anchor in place if `extend` is `true`.
+    #[inline]
+    pub(crate) fn move_to(&mut self, pos: usize, extend: bool) {
+        self.cursor = pos;
+
+        if !extend {
+            self.anchor = pos;
+        }
+    }
+
+    /// Returns `true` if there was a selection to delete.
+    pub(crate) fn delete_selection(&mut self, buf: &mut dyn TextBuf) -> bool {
+        if !self.has_selection() {
+            return false;
+        }
+
+        let selection = self.selection();
+        buf.remove(selection.clone());
+        self.move_to(selection.start, false);
+
+        true
+    }
+
+    /// The buffer may have been modified since the last frame.
+    pub(crate) fn clamp(&mut self, text: &str) {
+        let clamp = |mut pos: usize| {
+            pos = cmp::min(pos, text.len());
+
+            while !text.is_char_boundary(pos) {
+                pos -= 1;
+            }
+
+            pos
+        };
+
+        self.cursor = clamp(self.cursor);
+        self.anchor = clamp(self.anchor);
+    }
+}
+
+/// Returns the grapheme boundary closest to `x` pixels from the start of `text`.
+pub(crate) fn hit_test(handler: &dyn TextSizeHandler, font: FontId, text: &str, x: i32) -> usize {
+    let mut last = (0, 0);
+
+    for i in grapheme_boundaries(text).skip(1) {
+        let w = handler.text_width(font, &text[..i]);
+
+        if w >= x {
+            return if x - last.1 < w - x { last.0 } else { i };
+        }
+
+        last = (i, w);
+    }
+
+    last.0
+}
+
+#[inline]
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The start of the word before `pos`.
+fn prev_word(text: &str, pos: usize) -> usize {
+    let mut chars = text[..pos].char_indices().rev().peekable();
+    let mut result = pos;
+
+    while let Some((i, _)) = chars.next_if(|x| !is_word_char(x.1)) {
+        result = i;
+    }
+
+    while let Some((i, _)) = chars.next_if(|x| is_word_char(x.1)) {
+        result = i;
+    }
+
+    result
+}
+
+/// The end of the word after `pos`.
+fn next_word(text: &str, pos: usize) -> usize {
+    let mut chars = text[pos..].char_indices().peekable();
+    let mut result = pos;
+
+    while let Some((i, c)) = chars.next_if(|x| !is_word_char(x.1)) {
+        result = pos + i + c.len_utf8();
+    }
+
+    while let Some((i, c)) = chars.next_if(|x| is_word_char(x.1)) {
+        result = pos + i + c.len_utf8();
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Window, vec2};
+    use crate::test_util::Handler;
+
+    fn frame(ctx: &mut Context, buf: &mut ConstStr<64>) -> Option<EditState> {
+        let mut state = None;
+
+        ctx.begin();
+        Window::new("Test", rect(0, 0, 300, 100)).show(ctx, |ctx| {
+            ctx.layout_row(&[-1], 0);
+            ctx.textbox(buf);
+            state = ctx.text_edit_state(ctx.last_id().unwrap());
+        });
+        ctx.end();
+
+        state
+    }
+
+    fn press(ctx: &mut Context, buf: &mut ConstStr<64>, keys: &[Key]) -> EditState {
+        for key in keys {
+            ctx.input_key_down(*key);
+        }
+
+        let state = frame(ctx, buf).unwrap();
+
+        for key in keys {
+            ctx.input_key_up(*key);
+        }
+
+        state
+    }
+
+    #[test]
+    fn editing() {
+        let mut ctx = Context::new(Handler);
+        let mut buf = ConstStr::<64>::new();
+        buf.push_str("hello world");
+
+        // The text starts at x = 10 and each character is 8 pixels wide.
+        ctx.input_mouse_move(vec2(10 + 8 * 3 + 2, 40));
+        assert_eq!(frame(&mut ctx, &mut buf), None);
+        frame(&mut ctx, &mut buf);
+
+        ctx.input_mouse_down(vec2(10 + 8 * 3 + 2, 40), MouseButton::Left);
+        let state = frame(&mut ctx, &mut buf).unwrap();
+        ctx.input_mouse_up(vec2(10 + 8 * 3 + 2, 40), MouseButton::Left);
+        assert_eq!((state.cursor, state.anchor), (3, 3));
+
+        ctx.input_text("X");
+        let state = frame(&mut ctx, &mut buf).unwrap();
+        assert_eq!(buf.as_str(), "helXlo world");
+        assert_eq!(state.cursor, 4);
+
+        let state = press(&mut ctx, &mut buf, &[Key::Ctrl, Key::Right]);
+        assert_eq!(state.cursor, 6);
+
+        let state = press(&mut ctx, &mut buf, &[Key::Shift, Key::End]);
+        assert_eq!(state.selection(), 6..12);
+
+        press(&mut ctx, &mut buf, &[Key::Delete]);
+        assert_eq!(buf.as_str(), "helXlo");
+
+        let state = press(&mut ctx, &mut buf, &[Key::Home]);
+        assert_eq!(state.cursor, 0);
+
+        press(&mut ctx, &mut buf, &[Key::Delete]);
+        let state = press(&mut ctx, &mut buf, &[Key::Right]);
+        assert_eq!(buf.as_str(), "elXlo");
+        assert_eq!(state.cursor, 1);
+
+        press(&mut ctx, &mut buf, &[Key::Backspace]);
+        assert_eq!(buf.as_str(), "lXlo");
+
+        let state = press(&mut ctx, &mut buf, &[Key::Shift, Key::Right]);
+        assert_eq!(state.selection(), 0..1);
+
+        let state = press(&mut ctx, &mut buf, &[Key::Left]);
+        assert!(!state.has_selection());
+        assert_eq!(state.cursor, 0);
+    }
+
+    #[test]
+    fn clipboard() {
+        let mut ctx = Context::new(Handler);
+        let mut buf = ConstStr::<64>::new();
+        buf.push_str("copy me");
+
+        ctx.input_mouse_move(vec2(20, 40));
+        frame(&mut ctx, &mut buf);
+        frame(&mut ctx, &mut buf);
+        ctx.input_mouse_down(vec2(20, 40), MouseButton::Left);
+        frame(&mut ctx, &mut buf);
+        ctx.input_mouse_up(vec2(20, 40), MouseButton::Left);
+
+        press(&mut ctx, &mut buf, &[Key::Home]);
+        press(&mut ctx, &mut buf, &[Key::Ctrl, Key::Shift, Key::Right]);
+        press(&mut ctx, &mut buf, &[Key::Ctrl, Key::X]);
+        assert_eq!(buf.as_str(), " me");
+        assert_eq!(ctx.clipboard().get().as_deref(), Some("copy"));
+
+        press(&mut ctx, &mut buf, &[Key::End]);
+        ctx.clipboard().set("\nagain");
+        let state = press(&mut ctx, &mut buf, &[Key::Ctrl, Key::V]);
+        assert_eq!(buf.as_str(), " meagain");
+        assert_eq!(state.cursor, buf.len());
+
+        // Copying without a selection leaves the clipboard alone.
+        press(&mut ctx, &mut buf, &[Key::Ctrl, Key::C]);
+        assert_eq!(ctx.clipboard().get().as_deref(), Some("\nagain"));
+    }
+
+    #[test]
+    fn words() {
+        let text = "foo  bar_baz, qux";
+
+        assert_eq!(prev_word(text, text.len()), 14);
+        assert_eq!(prev_word(text, 14), 5);
+        assert_eq!(prev_word(text, 5), 0);
+        assert_eq!(next_word(text, 0), 3);
+        assert_eq!(next_word(text, 3), 12);
+        assert_eq!(next_word(text, 12), text.len());
+    }
+}